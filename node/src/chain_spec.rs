@@ -1,7 +1,7 @@
 use sp_core::{Pair, Public, sr25519};
 use node_subtensor_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, Signature
+	SudoConfig, SystemConfig, SubtensorModuleConfig, Signature
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -292,5 +292,16 @@ fn network_genesis(
 			// Assign network admin rights.
 			key: root_key,
 		}),
+		pallet_subtensor: Some(SubtensorModuleConfig {
+			pending_emissions: vec![],
+			stake: vec![],
+			transaction_fee_pool: 0,
+			// Register the initial modalities.
+			modalities: vec![
+				(0, b"TEXT".to_vec()),
+				(1, b"IMAGE".to_vec()),
+				(2, b"TENSOR".to_vec()),
+			],
+		}),
 	}
 }
@@ -0,0 +1,67 @@
+//! Runtime API definition for the subtensor pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Codec, Decode, Encode};
+use sp_arithmetic::FixedU128;
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+/// A single outgoing weight of a neuron.
+#[derive(Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WeightInfo {
+	/// The uid the weight points at.
+	pub uid: u64,
+	/// The normalized weight, the weights of a neuron sum to u32::MAX.
+	pub weight: u32,
+	/// The normalized weight as a fraction of u32::MAX.
+	pub fraction: FixedU128,
+}
+
+/// The outgoing weights of a neuron, one row of the weight matrix.
+#[derive(Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct NeuronWeights {
+	/// The uid of the neuron setting the weights.
+	pub uid: u64,
+	/// The weights of the neuron, sorted by uid.
+	pub weights: Vec<WeightInfo>,
+}
+
+/// The public key used by a neuron to authenticate its peer connections.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum TransportKey {
+	/// An ed25519 signing key, used for TLS and for signed challenges.
+	Ed25519([u8; 32]),
+	/// An x25519 static key, used for Noise handshakes.
+	X25519([u8; 32]),
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait SubtensorApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// Returns the uids of all neurons owned by the coldkey.
+		fn get_uids_for_coldkey(coldkey: AccountId) -> Vec<u64>;
+
+		/// Returns the sum of the stake of all neurons owned by the coldkey.
+		fn get_total_stake_for_coldkey(coldkey: AccountId) -> u64;
+
+		/// Returns the weights of the neuron with the uid, empty if the uid is vacant.
+		fn get_weights(uid: u64) -> Vec<WeightInfo>;
+
+		/// Returns the weights of the active neurons with uids in start_uid..start_uid + count.
+		/// A page covers at most 64 uids, larger counts are clamped.
+		fn get_weight_matrix(start_uid: u64, count: u64) -> Vec<NeuronWeights>;
+
+		/// Returns the transport key of the neuron with the uid, None if the uid is vacant
+		/// or the neuron has not registered a key.
+		fn get_transport_key(uid: u64) -> Option<TransportKey>;
+	}
+}
@@ -0,0 +1,102 @@
+//! RPC interface for the subtensor pallet.
+
+use std::sync::Arc;
+use codec::Codec;
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+use pallet_subtensor_rpc_runtime_api::{WeightInfo, NeuronWeights, TransportKey};
+pub use pallet_subtensor_rpc_runtime_api::SubtensorApi as SubtensorRuntimeApi;
+pub use self::gen_client::Client as SubtensorClient;
+
+#[rpc]
+pub trait SubtensorApi<BlockHash> {
+	/// Returns the normalized weights of the neuron with the uid.
+	#[rpc(name = "subtensor_getWeights")]
+	fn get_weights(&self, uid: u64, at: Option<BlockHash>) -> Result<Vec<WeightInfo>>;
+
+	/// Returns the normalized weights of the active neurons with uids in
+	/// start_uid..start_uid + count. A page covers at most 64 uids, larger counts
+	/// are clamped, so page through the metagraph by advancing start_uid.
+	#[rpc(name = "subtensor_getWeightMatrix")]
+	fn get_weight_matrix(&self, start_uid: u64, count: u64, at: Option<BlockHash>) -> Result<Vec<NeuronWeights>>;
+
+	/// Returns the transport key of the neuron with the uid.
+	#[rpc(name = "subtensor_getTransportKey")]
+	fn get_transport_key(&self, uid: u64, at: Option<BlockHash>) -> Result<Option<TransportKey>>;
+}
+
+/// A struct that implements the [`SubtensorApi`].
+pub struct Subtensor<C, P> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> Subtensor<C, P> {
+	/// Create new `Subtensor` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Subtensor { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error<E: std::fmt::Debug>(e: E) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query the runtime.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId> SubtensorApi<<Block as BlockT>::Hash> for Subtensor<C, (Block, AccountId)>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: SubtensorRuntimeApi<Block, AccountId>,
+	AccountId: Codec + Send + Sync + 'static,
+{
+	fn get_weights(&self, uid: u64, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<WeightInfo>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.get_weights(&at, uid).map_err(runtime_error)
+	}
+
+	fn get_weight_matrix(&self, start_uid: u64, count: u64, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<NeuronWeights>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.get_weight_matrix(&at, start_uid, count).map_err(runtime_error)
+	}
+
+	fn get_transport_key(&self, uid: u64, at: Option<<Block as BlockT>::Hash>) -> Result<Option<TransportKey>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.get_transport_key(&at, uid).map_err(runtime_error)
+	}
+}
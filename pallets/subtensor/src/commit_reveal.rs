@@ -0,0 +1,85 @@
+use super::*;
+use sp_runtime::traits::Hash;
+
+impl<T: Trait> Module<T> {
+    /// Stores the hash of the weights the neuron associated with the calling hotkey will
+    /// reveal. A new commit replaces the pending commit and restarts the reveal window.
+    /// The commit counts as an action of the hotkey for the liveness sweep.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The calling hotkey is not in the active set.
+    /// - SettingWeightsTooFast : The neuron set its weights, or made a commit it did not
+    ///   reveal, within the weights rate limit.
+    ///
+    pub fn do_commit_weights(origin: T::Origin, commit_hash: T::Hash) -> dispatch::DispatchResult {
+        // --- We check the callers (hotkey) signature.
+        let hotkey_id = ensure_signed(origin)?;
+        ensure!(Self::is_hotkey_active(&hotkey_id), Error::<T>::NotActive);
+
+        let uid = Self::get_uid_for_hotkey(&hotkey_id);
+        ensure!(Self::weights_commit_rate_limit_has_passed(uid), Error::<T>::SettingWeightsTooFast);
+
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        WeightCommits::<T>::insert(uid, (commit_hash, current_block));
+
+        // --- The commit proves the neuron is alive, as the set_weights of a reveal does.
+        Self::update_last_update_for_neuron(uid);
+
+        Self::deposit_event(RawEvent::WeightsCommitted(hotkey_id));
+        Ok(())
+    }
+
+    /// Checks the revealed weights against the pending commit of the neuron associated with
+    /// the calling hotkey and sets them through the checks of set_weights. The commit is
+    /// consumed once the weights are set. An expired commit can never be revealed, so it is
+    /// removed when the reveal is rejected.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The calling hotkey is not in the active set.
+    /// - NoWeightsCommit : The neuron has no pending commit.
+    /// - WeightsCommitExpired : The reveal window of the commit has passed.
+    /// - InvalidWeightsReveal : The revealed weights and salt do not match the commit.
+    /// - StaleMetagraph : The passed version does not match the current metagraph version.
+    /// - Any error of set_weights.
+    ///
+    pub fn do_reveal_weights(origin: T::Origin, uids: Vec<u64>, values: Vec<u32>, salt: Vec<u8>, version: Option<u64>) -> dispatch::DispatchResult {
+        // --- We check the callers (hotkey) signature.
+        let hotkey_id = ensure_signed(origin)?;
+        ensure!(Self::is_hotkey_active(&hotkey_id), Error::<T>::NotActive);
+
+        let uid = Self::get_uid_for_hotkey(&hotkey_id);
+        let (commit_hash, commit_block) = WeightCommits::<T>::get(uid).ok_or(Error::<T>::NoWeightsCommit)?;
+
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        if current_block > commit_block + T::WeightsRevealWindow::get() {
+            WeightCommits::<T>::remove(uid);
+            return Err(Error::<T>::WeightsCommitExpired.into());
+        }
+        ensure!(Self::get_weights_commit_hash(&hotkey_id, &uids, &values, &salt) == commit_hash, Error::<T>::InvalidWeightsReveal);
+        ensure!(Self::is_current_metagraph_version(version), Error::<T>::StaleMetagraph);
+
+        Self::set_weights_for_hotkey(hotkey_id, uids, values)?;
+        WeightCommits::<T>::remove(uid);
+        Ok(())
+    }
+
+    /// Returns true if the neuron may commit weights: the weights rate limit has passed since
+    /// it last set its weights and since its pending commit, if any, was made. A neuron
+    /// commits at most once per rate limit, as every reveal also sets its weights.
+    ///
+    pub fn weights_commit_rate_limit_has_passed(uid: u64) -> bool {
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        let commit_passed = match WeightCommits::<T>::get(uid) {
+            Some((_, commit_block)) => commit_block + Self::get_weights_set_rate_limit() <= current_block,
+            None => true,
+        };
+        commit_passed && Self::weights_rate_limit_has_passed(uid)
+    }
+
+    /// Returns the hash a neuron commits to before revealing its weights. The hotkey is
+    /// part of the preimage, so a commit copied by another neuron can not be revealed.
+    ///
+    pub fn get_weights_commit_hash(hotkey_id: &T::AccountId, uids: &Vec<u64>, values: &Vec<u32>, salt: &Vec<u8>) -> T::Hash {
+        T::Hashing::hash_of(&(hotkey_id, uids, values, salt))
+    }
+}
@@ -56,6 +56,12 @@ impl<T: Trait> Module<T> {
         // let callers_emission_u64_f64 = stake_fraction_u64_f64 * block_reward * elapsed_blocks;
         let pending_emission_for_neuron = Self::get_pending_emission_for_neuron(neuron.uid);
 
+        // --- Edges to removed or recycled uids are pruned before emitting, so the emission
+        // is distributed over the remaining edges instead of reaching a newcomer.
+        if Self::prune_stale_weights_for_neuron(neuron) {
+            Self::deposit_event(RawEvent::StaleWeightsPruned(vec![neuron.uid]));
+        }
+
         // --- We get the callers weights. The total emission will be distributed
         // according to these weights. The weight_vals sum to u32::max. ie. They have been normalized
         // to u32:max. This normalization takes places when weights are set. See fn set_weights
@@ -178,36 +184,58 @@ impl<T: Trait> Module<T> {
         return result.round().to_num::<u64>();
     }
 
-    /// Sets the pending emission for all active peers based on a single block transition.
+    /// Accrues the block reward of a single block transition for all active peers.
     ///
     /// This function is called every time a new block is started. ie, before the processing of
     /// extrinsics happens. In essence, this function integrates the proportional block reward for
     /// each neuron (which at a later point, can be distributed among its peers per the weights vector),
     /// with respect to the block number.
-    /// Every block, the proportional block reward is calculated per neuron, and put in the PendingEmission
-    /// map. If a value already exists for the neuron, the new reward is added to the existing value.
+    /// Instead of writing the PendingEmission of every neuron, the block reward per unit of stake is
+    /// added to the global RewardPerStake accumulator, so the cost of a block does not grow with the
+    /// metagraph. The pending emission of a neuron is its settled PendingEmission plus its stake
+    /// times the growth of RewardPerStake since its checkpoint. The accrued emission is settled
+    /// into PendingEmission before the stake or the inactive flag of the neuron changes.
     ///
     /// Then, when a neuron sets new weights, or when stake is added/removed, the emit_for_neuron function is
     /// called which distributes this pending emission among the peers in the weights vector.
     /// At this point, the PendingEmission is reset, and this cycle starts again.
+    ///
+    /// Neurons marked inactive by the liveness sweep do not accrue pending emission.
     pub fn update_pending_emissions() -> u64 {
-        let mut weight = 0;
         let block_reward = Self::get_reward_for_current_block();
         let total_stake = Self::get_total_stake();
 
         if total_stake == 0 {
-            return weight;
+            return 0;
         }
 
-        for (uid, neuron_stake) in <Stake as IterableStorageMap<u64, u64>>::iter() {
-            if neuron_stake == 0 { continue; }
-            let stake_fraction = Self::calulate_stake_fraction(neuron_stake, total_stake);
-            let new_emission = Self::calculate_new_emission(block_reward, stake_fraction);
-            Self::update_pending_emission_for_neuron(uid, new_emission);
+        // --- The accumulator wraps around, only the difference between two of its values is used.
+        let reward_per_stake = block_reward / U64F64::from_num(total_stake);
+        RewardPerStake::mutate(|accumulator| *accumulator = accumulator.wrapping_add(reward_per_stake.to_bits()));
+        1
+    }
 
-            weight += 1;
+    /// Returns the emission the neuron accrued since its checkpoint, which is not yet
+    /// settled into its PendingEmission. Inactive neurons accrue nothing.
+    pub fn get_accrued_emission_for_neuron(uid: u64) -> u64 {
+        let stake = Stake::get(uid);
+        if stake == 0 || Self::is_neuron_inactive(uid) {
+            return 0;
         }
-        weight
+
+        let reward_per_stake = U64F64::from_bits(RewardPerStake::get().wrapping_sub(RewardPerStakeCheckpoint::get(uid)));
+        return U64F64::from_num(stake).saturating_mul(reward_per_stake).to_num::<u64>();
+    }
+
+    /// Moves the emission the neuron accrued since its checkpoint into its PendingEmission
+    /// and moves the checkpoint to the current block. Must be called before the stake or the
+    /// inactive flag of the neuron changes, as the accrual assumes both were constant.
+    pub fn settle_pending_emission_for_neuron(uid: u64) {
+        let accrued = Self::get_accrued_emission_for_neuron(uid);
+        if accrued > 0 {
+            Self::update_pending_emission_for_neuron(uid, accrued);
+        }
+        RewardPerStakeCheckpoint::insert(uid, RewardPerStake::get());
     }
 
     /// This is a check to determine if an emission to a set of peers can proceed.
@@ -235,16 +263,19 @@ impl<T: Trait> Module<T> {
         return increment.to_num::<u64>()
     }
 
-    /// Returns the pending emission for a neuron in U64F64 format
+    /// Returns the pending emission for a neuron in U64F64 format, including the emission
+    /// accrued since its checkpoint.
     /// The default behaviour when a uid does not exist, is to return 0
     pub fn get_pending_emission_for_neuron(uid : u64) -> U64F64 {
-        U64F64::from_num( PendingEmission::get(uid) )
+        U64F64::from_num( PendingEmission::get(uid) ) + U64F64::from_num( Self::get_accrued_emission_for_neuron(uid) )
     }
 
-    /// Resets the pending emission for a neuron to zero
+    /// Resets the pending emission for a neuron to zero, including the emission accrued
+    /// since its checkpoint.
     pub fn reset_pending_emission_for_neuron(uid : u64 ) {
         PendingEmission::insert(uid, 0);
-    } 
+        RewardPerStakeCheckpoint::insert(uid, RewardPerStake::get());
+    }
 
 
     pub fn update_last_emit_for_neuron(uid: u64) {
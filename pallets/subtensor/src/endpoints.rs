@@ -0,0 +1,105 @@
+use super::*;
+
+impl<T: Trait> Module<T> {
+    /// Adds an endpoint to the neuron associated with the calling hotkey.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The calling hotkey is not in the active set.
+    /// - InvalidIpType : The ip type is not 4 or 6.
+    /// - The ip errors raised by ensure_ip_address_allowed.
+    /// - DuplicateEndpoint : The neuron already serves the protocol on this ip type, ip and port.
+    /// - EndpointInUse : Another neuron serves an endpoint on this ip and port.
+    /// - TooManyEndpoints : The neuron already serves MaxEndpointsPerNeuron endpoints.
+    /// - EndpointUpdateTooFast : The neuron updated its endpoints within the update cooldown.
+    /// - TooManyUpdatesThisBlock : The maximum number of updates this block is reached.
+    ///
+    pub fn do_add_endpoint(origin: T::Origin, ip: u128, port: u16, ip_type: u8, protocol: EndpointProtocol, version: u32) -> dispatch::DispatchResult {
+        // --- We check the callers (hotkey) signature.
+        let hotkey_id = ensure_signed(origin)?;
+        ensure!(Self::is_hotkey_active(&hotkey_id), Error::<T>::NotActive);
+
+        // --- The endpoint is validated with the same rules as the subscription endpoint.
+        ensure!(is_valid_ip_type(ip_type), Error::<T>::InvalidIpType);
+        Self::ensure_ip_address_allowed(ip_type, ip)?;
+
+        let uid = Self::get_uid_for_hotkey(&hotkey_id);
+        let mut endpoints = Self::get_endpoints_for_neuron(uid);
+        ensure!(!endpoints.iter().any(|e| e.ip_type == ip_type && e.ip == ip && e.port == port && e.protocol == protocol), Error::<T>::DuplicateEndpoint);
+        Self::ensure_endpoint_not_in_use(uid, ip_type, ip, port)?;
+        ensure!((endpoints.len() as u32) < T::MaxEndpointsPerNeuron::get(), Error::<T>::TooManyEndpoints);
+        ensure!(Self::update_cooldown_has_passed(uid), Error::<T>::EndpointUpdateTooFast);
+        ensure!(Self::check_and_increment_updates_per_block(), Error::<T>::TooManyUpdatesThisBlock);
+
+        let index = endpoints.len() as u32;
+        endpoints.push(NeuronEndpoint { ip, port, ip_type, protocol, version });
+        Endpoints::insert(uid, endpoints);
+        Self::claim_endpoint_address(uid, ip_type, ip, port);
+        Self::update_last_endpoint_update_for_neuron(uid);
+        Self::update_last_update_for_neuron(uid);
+
+        Self::deposit_event(RawEvent::EndpointAdded(uid, index));
+        Ok(())
+    }
+
+    /// Removes the endpoint in slot index from the neuron associated with the calling hotkey.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The calling hotkey is not in the active set.
+    /// - PrimaryEndpointNotRemovable : The index is 0, the subscription endpoint.
+    /// - InvalidEndpointIndex : The neuron has no endpoint in this slot.
+    /// - EndpointUpdateTooFast : The neuron updated its endpoints within the update cooldown.
+    /// - TooManyUpdatesThisBlock : The maximum number of updates this block is reached.
+    ///
+    pub fn do_remove_endpoint(origin: T::Origin, index: u32) -> dispatch::DispatchResult {
+        // --- We check the callers (hotkey) signature.
+        let hotkey_id = ensure_signed(origin)?;
+        ensure!(Self::is_hotkey_active(&hotkey_id), Error::<T>::NotActive);
+        ensure!(index != 0, Error::<T>::PrimaryEndpointNotRemovable);
+
+        let uid = Self::get_uid_for_hotkey(&hotkey_id);
+        let mut endpoints = Self::get_endpoints_for_neuron(uid);
+        ensure!((index as usize) < endpoints.len(), Error::<T>::InvalidEndpointIndex);
+        ensure!(Self::update_cooldown_has_passed(uid), Error::<T>::EndpointUpdateTooFast);
+        ensure!(Self::check_and_increment_updates_per_block(), Error::<T>::TooManyUpdatesThisBlock);
+
+        let removed = endpoints.remove(index as usize);
+        Self::release_endpoint_address(uid, removed.ip_type, removed.ip, removed.port, &endpoints);
+        Endpoints::insert(uid, endpoints);
+        Self::update_last_endpoint_update_for_neuron(uid);
+        Self::update_last_update_for_neuron(uid);
+
+        Self::deposit_event(RawEvent::EndpointRemoved(uid, index));
+        Ok(())
+    }
+
+    /// Returns the endpoints served by the neuron. Slot 0 always holds the subscription endpoint.
+    /// Neurons which subscribed before endpoints existed are seeded by the storage migration.
+    ///
+    pub fn get_endpoints_for_neuron(uid: u64) -> Vec<NeuronEndpoint> {
+        return Endpoints::get(uid);
+    }
+
+    /// Writes the subscription endpoint into slot 0 of the neuron's endpoints, keeping
+    /// all additional endpoints.
+    ///
+    pub fn set_primary_endpoint_for_neuron(uid: u64, ip: u128, port: u16, ip_type: u8) {
+        let mut endpoints = Endpoints::get(uid);
+        let primary = Self::primary_endpoint(ip, port, ip_type);
+        if endpoints.is_empty() {
+            endpoints.push(primary);
+        } else {
+            endpoints[0] = primary;
+        }
+        Endpoints::insert(uid, endpoints);
+    }
+
+    fn primary_endpoint(ip: u128, port: u16, ip_type: u8) -> NeuronEndpoint {
+        NeuronEndpoint {
+            ip,
+            port,
+            ip_type,
+            protocol: EndpointProtocol::Grpc,
+            version: 0,
+        }
+    }
+}
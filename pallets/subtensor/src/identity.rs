@@ -0,0 +1,88 @@
+use super::*;
+
+impl<T: Trait> Module<T> {
+    /// Sets the identity of the neuron associated with the hotkey. The first identity of a
+    /// neuron reserves IdentityDeposit from the coldkey, later calls keep the reserved deposit.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The supplied hotkey is not in the active set.
+    /// - NonAssociatedColdKey : The caller is not the coldkey of the neuron.
+    /// - ColdkeyNotConfirmed : The coldkey has not accepted the hotkey.
+    /// - IdentityFieldTooLong : A field is longer than MaxIdentityFieldLength.
+    /// - NotEnoughBalanceForIdentityDeposit : The deposit can not be reserved from the coldkey.
+    ///
+    pub fn do_set_identity(origin: T::Origin, hotkey: T::AccountId, display: Option<Vec<u8>>, url: Option<Vec<u8>>, contact: Option<Vec<u8>>, software_version: Option<Vec<u8>>) -> dispatch::DispatchResult {
+
+        // --- We check the callers (coldkey) signature.
+        let coldkey = ensure_signed(origin)?;
+
+        // --- We check that the hotkey is active and owned by the calling coldkey.
+        ensure!(Self::is_hotkey_active(&hotkey), Error::<T>::NotActive);
+        let neuron = Self::get_neuron_for_hotkey(&hotkey);
+        ensure!(Self::neuron_belongs_to_coldkey(&neuron, &coldkey), Error::<T>::NonAssociatedColdKey);
+        ensure!(neuron.coldkey_confirmed, Error::<T>::ColdkeyNotConfirmed);
+
+        // --- We check the length of every field.
+        let max_length = T::MaxIdentityFieldLength::get() as usize;
+        for field in [&display, &url, &contact, &software_version].iter() {
+            ensure!(field.as_ref().map_or(true, |value| value.len() <= max_length), Error::<T>::IdentityFieldTooLong);
+        }
+
+        // --- A new identity reserves the deposit, an update keeps the reserved deposit.
+        let deposit = match Identities::<T>::get(neuron.uid) {
+            Some(identity) => identity.deposit,
+            None => {
+                let deposit = T::IdentityDeposit::get();
+                ensure!(T::Currency::reserve(&coldkey, deposit).is_ok(), Error::<T>::NotEnoughBalanceForIdentityDeposit);
+                deposit
+            }
+        };
+
+        Identities::<T>::insert(neuron.uid, NeuronIdentityOf::<T> {
+            display,
+            url,
+            contact,
+            software_version,
+            deposit,
+        });
+
+        // --- We deposit the identity set event.
+        Self::deposit_event(RawEvent::IdentitySet(neuron.uid));
+        Ok(())
+    }
+
+    /// Clears the identity of the neuron associated with the hotkey and returns the deposit
+    /// to the coldkey.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The supplied hotkey is not in the active set.
+    /// - NonAssociatedColdKey : The caller is not the coldkey of the neuron.
+    /// - ColdkeyNotConfirmed : The coldkey has not accepted the hotkey.
+    /// - NoIdentity : The neuron has no identity.
+    ///
+    pub fn do_clear_identity(origin: T::Origin, hotkey: T::AccountId) -> dispatch::DispatchResult {
+
+        // --- We check the callers (coldkey) signature.
+        let coldkey = ensure_signed(origin)?;
+
+        // --- We check that the hotkey is active and owned by the calling coldkey.
+        ensure!(Self::is_hotkey_active(&hotkey), Error::<T>::NotActive);
+        let neuron = Self::get_neuron_for_hotkey(&hotkey);
+        ensure!(Self::neuron_belongs_to_coldkey(&neuron, &coldkey), Error::<T>::NonAssociatedColdKey);
+        ensure!(neuron.coldkey_confirmed, Error::<T>::ColdkeyNotConfirmed);
+        ensure!(Identities::<T>::contains_key(neuron.uid), Error::<T>::NoIdentity);
+
+        Self::remove_identity_for_neuron(&neuron);
+        Ok(())
+    }
+
+    /// Removes the identity of the neuron, if any, and unreserves its deposit on the coldkey.
+    /// Called when the identity is cleared and when the neuron leaves the metagraph.
+    ///
+    pub fn remove_identity_for_neuron(neuron: &NeuronMetadataOf<T>) {
+        if let Some(identity) = Identities::<T>::take(neuron.uid) {
+            T::Currency::unreserve(&neuron.coldkey, identity.deposit);
+            Self::deposit_event(RawEvent::IdentityCleared(neuron.uid));
+        }
+    }
+}
@@ -1,10 +1,10 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 // --- Frame imports.bug
-use frame_support::{decl_module, decl_storage, decl_event, decl_error, dispatch, dispatch::IsSubType, ensure, debug, IterableStorageMap, traits::{Currency, WithdrawReasons, WithdrawReason, ExistenceRequirement}, Printable};
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, dispatch, dispatch::IsSubType, ensure, debug, IterableStorageMap, IterableStorageDoubleMap, traits::{Currency, ReservableCurrency, WithdrawReasons, WithdrawReason, ExistenceRequirement}, Printable};
 use frame_support::weights::{DispatchClass, Pays};
 use codec::{Decode, Encode};
-use frame_system::{self as system, ensure_signed};
+use frame_system::{self as system, ensure_signed, ensure_root};
 use substrate_fixed::types::U64F64;
 use sp_std::convert::TryInto;
 use sp_std::{
@@ -27,6 +27,8 @@ use sp_runtime::{
 };
 
 use pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo;
+use pallet_subtensor_rpc_runtime_api::{WeightInfo, NeuronWeights};
+pub use pallet_subtensor_rpc_runtime_api::TransportKey;
 
 use sp_runtime::traits::{Dispatchable};
 use frame_support::traits::Get;
@@ -39,6 +41,13 @@ mod staking;
 mod subscribing;
 mod emission;
 mod block_reward;
+mod liveness;
+mod endpoints;
+mod migration;
+mod identity;
+mod transport;
+mod commit_reveal;
+mod weights_key;
 
 /// --- Configure the pallet by specifying the parameters and types on which it depends.
 pub trait Trait: frame_system::Trait {
@@ -46,22 +55,114 @@ pub trait Trait: frame_system::Trait {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 
     /// --- Currency type that will be used to place deposits on neurons
-    type Currency: Currency<Self::AccountId> + Send + Sync;
+    type Currency: ReservableCurrency<Self::AccountId> + Send + Sync;
 
     /// - The transaction fee in RAO per byte
     type TransactionByteFee: Get<BalanceOf<Self>>;
+
+    /// --- The maximum number of neurons in the metagraph. When this number is reached
+    /// a new subscription replaces the lowest ranked neuron.
+    type MaxNeurons: Get<u64>;
+
+    /// --- The number of blocks after subscription during which a neuron can not be
+    /// replaced by a new subscription.
+    type ImmunityPeriod: Get<Self::BlockNumber>;
+
+    /// --- The default maximum number of new neurons which can subscribe in a single block.
+    /// Root can override this value through set_subscription_rate_limits.
+    type MaxRegistrationsPerBlock: Get<u32>;
+
+    /// --- The default maximum number of endpoint updates (resubscriptions of active neurons)
+    /// in a single block. Root can override this value through set_subscription_rate_limits.
+    type MaxUpdatesPerBlock: Get<u32>;
+
+    /// --- The default number of blocks a neuron must wait between two endpoint updates.
+    /// Root can override this value through set_subscription_rate_limits.
+    type UpdateCooldown: Get<Self::BlockNumber>;
+
+    /// --- The number of blocks without a hotkey action after which a neuron is marked
+    /// inactive. Inactive neurons do not accrue pending emission until they subscribe again.
+    type InactivityThreshold: Get<Self::BlockNumber>;
+
+    /// --- The maximum number of uids checked for inactivity at the start of each block.
+    type MaxLivenessChecksPerBlock: Get<u64>;
+
+    /// --- The maximum number of endpoints a neuron can register, including the
+    /// endpoint passed on subscription.
+    type MaxEndpointsPerNeuron: Get<u32>;
+
+    /// --- The maximum number of root controlled ip range overrides. Every subscription
+    /// checks its address against all of them.
+    type MaxIpRangeOverrides: Get<u32>;
+
+    /// --- The deposit reserved from the coldkey balance while a neuron has an identity.
+    type IdentityDeposit: Get<BalanceOf<Self>>;
+
+    /// --- The maximum length in bytes of each neuron identity field.
+    type MaxIdentityFieldLength: Get<u32>;
+
+    /// --- The maximum number of outward edges in the weights of a neuron.
+    type MaxWeightsPerNeuron: Get<u32>;
+
+    /// --- The default number of blocks a neuron must wait between two weight updates.
+    /// Root can override this value through set_weights_rate_limit.
+    type WeightsSetRateLimit: Get<Self::BlockNumber>;
+
+    /// --- The number of blocks after a weights commit during which it can be revealed.
+    type WeightsRevealWindow: Get<Self::BlockNumber>;
+
+    /// --- The maximum number of weight rows checked for stale edges at the start of each block.
+    /// A row can hold MaxWeightsPerNeuron edges, each of which is read, so keep it small.
+    type MaxWeightPrunesPerBlock: Get<u64>;
 }
 
 // ---- Create account types for the NeuronMetadata struct.
 type AccountIdOf<T> = <T as system::Trait>::AccountId;
 type NeuronMetadataOf<T> = NeuronMetadata<AccountIdOf<T>>;
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type NeuronIdentityOf<T> = NeuronIdentity<BalanceOf<T>>;
+
 
 
+// ---- The version of the NeuronMetadata layout written by this runtime.
+// Increase this and add a migration in migration.rs when fields are added.
+pub const NEURON_METADATA_VERSION: u8 = 2;
+
+// ---- The maximum number of uids covered by one page of the weight matrix,
+// larger requests are clamped to it. See fn get_weight_matrix.
+pub const MAX_WEIGHT_MATRIX_PAGE: u64 = 64;
+
+// ---- The storage layout versions of this pallet.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Releases {
+    /// ---- Unversioned NeuronMetadata without coldkey confirmation.
+    V1_0_0,
+
+    /// ---- Versioned NeuronMetadata with coldkey confirmation, coldkey
+    /// and modality indexes and neuron endpoints.
+    V2_0_0,
+
+    /// ---- NeuronMetadata with an optional transport public key.
+    V3_0_0,
+
+    /// ---- Weights stored as a single sorted vector of (uid, weight) edges.
+    V4_0_0,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1_0_0
+    }
+}
 
 // ---- Neuron endpoint information
 #[derive(Encode, Decode, Default)]
 pub struct NeuronMetadata<AccountId> {
+    /// ---- The layout version of this entry, NEURON_METADATA_VERSION
+    /// for entries written by this runtime.
+    pub version: u8,
+
     /// ---- The endpoint's u128 encoded ip address of type v6 or v4.
     pub ip: u128,
 
@@ -81,7 +182,8 @@ pub struct NeuronMetadata<AccountId> {
     /// the neuron endpoint can process. This information is non
     /// verifiable. However, neurons should set this correctly
     /// in order to be detected by others with this datatype.
-    /// The initial modality codes are:
+    /// Valid modality codes are kept in the Modalities registry,
+    /// the initial modality codes are:
     /// TEXT: 0
     /// IMAGE: 1
     /// TENSOR: 2
@@ -98,12 +200,90 @@ pub struct NeuronMetadata<AccountId> {
     /// The hotkey account (in the Neurons map) has permission to call emit
     /// subscribe and unsubscribe.
     pub coldkey: AccountId,
+
+    /// ---- True when the coldkey has accepted the association with the
+    /// hotkey through accept_hotkey. A hotkey can name any coldkey on
+    /// subscription, the coldkey can only stake, unstake, swap the hotkey
+    /// or unsubscribe the neuron once the association is confirmed.
+    pub coldkey_confirmed: bool,
+
+    /// ---- The public key peers use to authenticate connections to the
+    /// neuron's endpoint. Registered by the hotkey through set_transport_key.
+    pub transport_key: Option<TransportKey>,
+}
+
+// ---- Self reported identity of a neuron. Set by the coldkey of the neuron,
+// the fields are not verified by the chain.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct NeuronIdentity<Balance> {
+    /// ---- The display name of the neuron.
+    pub display: Option<Vec<u8>>,
+
+    /// ---- A url with information about the neuron.
+    pub url: Option<Vec<u8>>,
+
+    /// ---- Contact information of the neuron operator.
+    pub contact: Option<Vec<u8>>,
+
+    /// ---- The version string of the software run by the neuron.
+    pub software_version: Option<Vec<u8>>,
+
+    /// ---- The deposit reserved from the coldkey for this identity.
+    pub deposit: Balance,
+}
+
+// ---- The protocol served on a neuron endpoint.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum EndpointProtocol {
+    /// ---- The grpc axon endpoint, used for the subscription endpoint.
+    Grpc,
+
+    /// ---- A plain http endpoint.
+    Http,
+
+    /// ---- A prometheus metrics endpoint.
+    Prometheus,
+
+    /// ---- A peer to peer gossip endpoint.
+    Gossip,
+}
+
+impl Default for EndpointProtocol {
+    fn default() -> Self {
+        EndpointProtocol::Grpc
+    }
+}
+
+// ---- A single typed endpoint served by a neuron.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct NeuronEndpoint {
+    /// ---- The endpoint's u128 encoded ip address of type v6 or v4.
+    pub ip: u128,
+
+    /// ---- The endpoint's u16 encoded port.
+    pub port: u16,
+
+    /// ---- The endpoint's ip type, 4 for ipv4 and 6 for ipv6.
+    pub ip_type: u8,
+
+    /// ---- The protocol served on the endpoint.
+    pub protocol: EndpointProtocol,
+
+    /// ---- The version of the protocol served on the endpoint.
+    pub version: u32,
 }
 
 // ---- Subtensor storage items.
 decl_storage! {
 	trait Store for Module<T: Trait> as SubtensorModule {
 
+		/// ---- The storage layout version of this pallet. Set to the latest
+		/// version at genesis and updated by the migrations in on_runtime_upgrade.
+		pub StorageVersion get(fn storage_version): Releases;
+
 		/// ---- Active set map between a hotkey account and network uids.
 		/// Used by subtensor for checking peer existence.
 		pub Active get(fn uid): map hasher(blake2_128_concat) T::AccountId => u64;
@@ -113,26 +293,84 @@ decl_storage! {
 		/// The metadata contains that uid, the ip, port, and coldkey address.
 		pub Neurons get(fn neuron): map hasher(identity) u64 => NeuronMetadataOf<T>;
 
+		/// ---- Maps between a neuron's uid and its self reported identity.
+		pub Identities get(fn identity): map hasher(identity) u64 => Option<NeuronIdentityOf<T>>;
+
+		/// ---- Maps between a neuron's uid and the endpoints it serves. Slot 0 mirrors
+		/// the ip, port and ip type of the NeuronMetadata and is updated on resubscription.
+		/// Neurons which subscribed before endpoints existed are seeded from their
+		/// NeuronMetadata by the storage migration, see fn migrate_to_v2.
+		pub Endpoints get(fn endpoints): map hasher(identity) u64 => Vec<NeuronEndpoint>;
+
+		/// ---- Maps between a neuron's uid and the block number at which
+		/// the neuron subscribed. Used to protect new neurons from being replaced.
+		pub BlockAtRegistration get(fn block_at_registration): map hasher(identity) u64 => T::BlockNumber;
+
 		/// ---- Maps between a neuron's hotkey uid and the block number
 		/// when that peer last called an emission/subscribe.
 		pub LastEmit get(fn last_emit): map hasher(identity) u64 => T::BlockNumber;
 
+		/// ---- Maps between a neuron's uid and the block number at which its hotkey
+		/// last subscribed or set weights. Used to detect idle neurons.
+		pub LastUpdate get(fn last_update): map hasher(identity) u64 => T::BlockNumber;
+
+		/// ---- The uids marked inactive by the liveness sweep. Inactive neurons
+		/// do not accrue pending emission.
+		pub InactiveNeurons get(fn inactive_neuron): map hasher(identity) u64 => bool;
+
+		/// ---- The uid at which the next liveness sweep starts.
+		LivenessSweepCursor: u64;
+
+		/// ---- Counter bumped whenever a neuron is added to or removed from the metagraph.
+		pub MetagraphVersion get(fn metagraph_version): u64;
+
+		/// ---- Maps between a uid and the metagraph version at which the neuron holding
+		/// the uid was added.
+		pub UidVersion get(fn uid_version): map hasher(identity) u64 => u64;
+
+		/// ---- Maps between a neuron's uid and the metagraph version at which its weights
+		/// were last written. An edge to a uid added at a later version is stale.
+		pub WeightsVersion get(fn weights_version): map hasher(identity) u64 => u64;
+
+		/// ---- The number of weight rows left to check for stale edges, and the uid at
+		/// which the next stale weights sweep starts.
+		PendingWeightPrunes: u64;
+		WeightsPruneCursor: u64;
+
 		/// --- Maps between a neuron's hotkey uid and this peer's pending emission.
 		/// pending emission is the quantity 
 		pub PendingEmission get(fn pending_emission ): map hasher(identity) u64 => u64;
 
+		/// ---- The cumulative block reward per unit of stake since genesis, stored as the
+		/// bits of a U64F64. It is increased once per block, and the emission a neuron accrues
+		/// since its checkpoint is added to its pending emission lazily.
+		RewardPerStake: u128;
+
+		/// ---- Maps between a neuron's uid and the RewardPerStake at which its pending
+		/// emission was last settled.
+		RewardPerStakeCheckpoint: map hasher(identity) u64 => u128;
+
 		/// ---- List of values which map between a neuron's uid an that neuron's
 		/// weights, a.k.a is row_weights in the square matrix W. Each outward edge
-		/// is represented by a (u64, u64) tuple determining the endpoint and weight
-		/// value respectively. Each giga byte of chain storage can hold history for
-		/// 83 million weights. 
-		pub WeightUids: map hasher(identity) u64 => Vec<u64>;
-		pub WeightVals: map hasher(identity) u64 => Vec<u32>;
+		/// is represented by a (u64, u32) tuple determining the endpoint and weight
+		/// value respectively. The edges are sorted by endpoint uid and bounded by
+		/// MaxWeightsPerNeuron. Each giga byte of chain storage can hold history for
+		/// 83 million weights.
+		pub Weights get(fn weights): map hasher(identity) u64 => Vec<(u64, u32)>;
 		
 		/// ----  Maps between a neuron's hotkey uid and the number of
 		/// staked tokens under that key.
 		pub Stake get(fn stake): map hasher(identity) u64 => u64;
 
+		/// ---- Index of the uids owned by each coldkey. A neuron is added to the index
+		/// of its coldkey once the coldkey has confirmed the association, either through
+		/// accept_hotkey or by subscribing with its own hotkey as coldkey.
+		pub ColdkeyNeurons: double_map hasher(blake2_128_concat) T::AccountId, hasher(identity) u64 => ();
+
+		/// ---- Maps between a coldkey and the sum of the stake of the neurons
+		/// in its ColdkeyNeurons index.
+		pub TotalColdkeyStake get(fn total_coldkey_stake): map hasher(blake2_128_concat) T::AccountId => u64;
+
 		/// ---- Stores the amount of currently staked token.
 		TotalStake: u64;
 
@@ -140,22 +378,105 @@ decl_storage! {
 		/// have ever subscribed.
 		NextUID: u64;
 
-		/// ---- The number of subscriptions this block, used in conjunction with 
-		SubscriptionsThisBlock: u32;
-		LastSubscriptionBlock: T::BlockNumber;
+		/// ---- The uids left vacant by removed neurons. They are handed out before a fresh
+		/// uid is allocated, so uids stay within 0..MaxNeurons.
+		pub VacantUids get(fn vacant_uids): Vec<u64>;
+
+		/// ---- The number of new registrations this block, used in conjunction with
+		/// the block they were counted in to rate limit new subscriptions.
+		RegistrationsThisBlock: u32;
+		LastRegistrationBlock: T::BlockNumber;
+
+		/// ---- The number of endpoint updates this block, used in conjunction with
+		/// the block they were counted in to rate limit resubscriptions.
+		UpdatesThisBlock: u32;
+		LastUpdateBlock: T::BlockNumber;
+
+		/// ---- Maps between a neuron's uid and the block number at which the neuron
+		/// last subscribed or updated its endpoint. Used for the update cooldown.
+		pub LastEndpointUpdate get(fn last_endpoint_update): map hasher(identity) u64 => T::BlockNumber;
+
+		/// ---- Root controlled overrides of the MaxRegistrationsPerBlock, MaxUpdatesPerBlock
+		/// and UpdateCooldown Trait constants. When empty the Trait constant is used.
+		MaxRegistrationsPerBlockOverride: Option<u32>;
+		MaxUpdatesPerBlockOverride: Option<u32>;
+		UpdateCooldownOverride: Option<T::BlockNumber>;
+
+		/// ---- Maps between a neuron's uid and the block number at which the neuron
+		/// last set its weights. Used for the weights rate limit.
+		pub LastWeightsSet get(fn last_weights_set): map hasher(identity) u64 => Option<T::BlockNumber>;
+
+		/// ---- Root controlled override of the WeightsSetRateLimit Trait constant.
+		/// When empty the Trait constant is used.
+		WeightsSetRateLimitOverride: Option<T::BlockNumber>;
+
+		/// ---- Maps between a neuron's uid and its pending weights commit: the hash of
+		/// the weights to reveal and the block number of the commit.
+		pub WeightCommits get(fn weight_commits): map hasher(identity) u64 => Option<(T::Hash, T::BlockNumber)>;
+
+		/// ---- Maps between a neuron's uid and the weights key its coldkey authorized to
+		/// set weights on behalf of the hotkey.
+		pub WeightsKeys get(fn weights_key): map hasher(identity) u64 => Option<T::AccountId>;
+
+		/// ---- Maps between a weights key and the uid of the neuron it sets weights for.
+		pub WeightsKeyUid get(fn uid_for_weights_key): map hasher(blake2_128_concat) T::AccountId => Option<u64>;
 
 		/// ---- The total amount of transaction fees accumulated during a block
 		TransactionFeePool: u64;
 
 		/// --- The transaction fees that are added to the current block reward.
 		TransactionFeesForBlock : u64;
+
+		/// ---- Registry of the modalities a neuron can subscribe with. Maps between
+		/// a modality code and its name. Managed by root through add_modality and
+		/// retire_modality.
+		pub Modalities get(fn modality): map hasher(identity) u8 => Option<Vec<u8>>;
+
+		/// ---- Index of the uids subscribed under each modality. Allows clients
+		/// to find all neurons of a modality without scanning Neurons.
+		pub ModalityUids: double_map hasher(identity) u8, hasher(identity) u64 => ();
+
+		/// ---- Root controlled overrides of the subscription ip address policy. Maps between
+		/// an (ip_type, network, prefix length) range and whether addresses in that range are
+		/// allowed (true) or denied (false). A deny entry takes precedence over an allow entry.
+		pub IpRangeOverrides get(fn ip_range_override): map hasher(blake2_128_concat) (u8, u128, u8) => Option<bool>;
+
+		/// ---- The number of entries in IpRangeOverrides, bounded by MaxIpRangeOverrides.
+		pub IpRangeOverrideCount get(fn ip_range_override_count): u32;
+
+		/// ---- Index between a subscription endpoint (ip_type, ip, port) and the uid of
+		/// the neuron serving it. A subscription endpoint can be served by one neuron only.
+		pub EndpointUids get(fn endpoint_uid): map hasher(blake2_128_concat) (u8, u128, u16) => Option<u64>;
+
+		/// ---- The number of neurons subscribed with each (ip_type, ip) address.
+		pub UidsPerIp get(fn uids_per_ip): map hasher(blake2_128_concat) (u8, u128) => u32;
+
+		/// ---- Root controlled limit on the number of neurons which can subscribe
+		/// with the same ip address. 0 means unlimited.
+		pub MaxUidsPerIp get(fn max_uids_per_ip): u32;
+
+		/// ---- Root controlled limits on the normalized weights of a neuron, checked in
+		/// set_weights. Weights are normalized to u32::MAX, so u32::MAX means no limit.
+		/// The maximum weight a neuron can set on itself.
+		pub MaxSelfWeight get(fn max_self_weight): u32 = u32::max_value();
+
+		/// ---- The minimum number of edges to other neurons in the weights of a neuron.
+		pub MinNonSelfWeights get(fn min_non_self_weights): u32;
+
+		/// ---- The maximum weight a neuron can set on any single edge.
+		pub MaxEdgeWeight get(fn max_edge_weight): u32 = u32::max_value();
 	}
 
 	add_extra_genesis {
         config(pending_emissions): Vec<(u64, u64)>;
         config(stake): Vec<(u64, u64)>;
         config(transaction_fee_pool): u64;
+        config(modalities): Vec<(u8, Vec<u8>)>;
         build(|config| {
+            for (modality, name) in &config.modalities {
+                Modalities::insert(modality, name);
+            };
+
             for (uid, emission) in &config.pending_emissions {
                 PendingEmission::insert(uid, emission);
             };
@@ -167,17 +488,30 @@ decl_storage! {
             if config.transaction_fee_pool > 0 {
                 TransactionFeePool::put(&config.transaction_fee_pool);
             }
+
+            StorageVersion::put(Releases::V4_0_0);
         })
     }
 }
 
 // ---- Subtensor events.
 decl_event!(
-	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
+	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId, BlockNumber = <T as frame_system::Trait>::BlockNumber {
 		/// ---- Event created when a caller successfully set's their weights
 		/// on the chain.
 		WeightsSet(AccountId),
 
+		/// ---- Event created when a neuron commits to the hash of its next weights.
+		WeightsCommitted(AccountId),
+
+		/// ---- Event created when a coldkey authorizes a weights key for a neuron.
+		/// Contains the hotkey of the neuron and the weights key.
+		WeightsKeySet(AccountId, AccountId),
+
+		/// ---- Event created when the weights key of a neuron is revoked, by its coldkey
+		/// or because the weights key became a hotkey. Contains the hotkey of the neuron.
+		WeightsKeyRevoked(AccountId),
+
 		/// --- Event created when a new neuron account has been subscribed to 
 		/// the neuron set.
 		NeuronAdded(u64),
@@ -186,6 +520,44 @@ decl_event!(
 		/// is changed, for instance, when the ip/port changes.
 		NeuronUpdated(u64),
 
+		/// --- Event created when a neuron is marked inactive because its hotkey
+		/// has not subscribed or set weights for InactivityThreshold blocks.
+		NeuronInactive(u64),
+
+		/// --- Event created when stale edges, pointing at removed or recycled uids, are
+		/// pruned from weights. Contains the uids of the neurons whose weights changed.
+		StaleWeightsPruned(Vec<u64>),
+
+		/// --- Event created when a neuron adds an endpoint. Contains the uid
+		/// and the slot of the new endpoint.
+		EndpointAdded(u64, u32),
+
+		/// --- Event created when a neuron removes an endpoint. Contains the uid
+		/// and the slot of the removed endpoint.
+		EndpointRemoved(u64, u32),
+
+		/// --- Event created when a neuron has been removed from the
+		/// neuron set, either by its hotkey or by its coldkey.
+		NeuronRemoved(u64),
+
+		/// --- Event created when a coldkey confirms its association with a neuron.
+		/// Contains the uid and the coldkey.
+		ColdkeyConfirmed(u64, AccountId),
+
+		/// --- Event created when a neuron sets or removes its transport key.
+		TransportKeySet(u64),
+
+		/// --- Event created when the coldkey sets the identity of a neuron.
+		IdentitySet(u64),
+
+		/// --- Event created when the identity of a neuron is cleared and its
+		/// deposit returned to the coldkey.
+		IdentityCleared(u64),
+
+		/// --- Event created when the coldkey of a neuron has replaced its hotkey.
+		/// Contains the uid, the old hotkey and the new hotkey.
+		HotkeySwapped(u64, AccountId, AccountId),
+
 		/// --- Event created during when stake has been transfered from 
 		/// the coldkey onto the hotkey staking account.
 		StakeAdded(AccountId, u64),
@@ -197,6 +569,36 @@ decl_event!(
 		/// --- Event created when a transaction triggers and incentive
 		/// mechanism emission.
 		Emission(AccountId, u64),
+
+		/// --- Event created when root sets the subscription rate limits. Contains the
+		/// maximum registrations per block, the maximum endpoint updates per block and the
+		/// update cooldown in effect.
+		SubscriptionRateLimitsSet(u32, u32, BlockNumber),
+
+		/// --- Event created when root sets the weights rate limit. Contains the number
+		/// of blocks between two weight updates in effect.
+		WeightsSetRateLimitSet(BlockNumber),
+
+		/// --- Event created when root adds a modality to the registry.
+		/// Contains the modality code and its name.
+		ModalityAdded(u8, Vec<u8>),
+
+		/// --- Event created when root retires a modality from the registry.
+		ModalityRetired(u8),
+
+		/// --- Event created when root sets an ip range override. Contains the
+		/// ip type, network, prefix length and whether the range is allowed.
+		IpRangeOverrideSet(u8, u128, u8, bool),
+
+		/// --- Event created when root removes an ip range override.
+		IpRangeOverrideRemoved(u8, u128, u8),
+
+		/// --- Event created when root sets the maximum number of neurons per ip address.
+		MaxUidsPerIpSet(u32),
+
+		/// --- Event created when root sets the weight limits. Contains the maximum
+		/// self weight, the minimum number of non self edges and the maximum edge weight.
+		WeightLimitsSet(u32, u32, u32),
 	}
 );
 
@@ -208,16 +610,98 @@ decl_error! {
 		InvalidIpType,
 
 		/// --- Thrown when an invalid IP address is passed to the subscribe function.
+		/// For instance the unspecified address, the broadcast address or an ipv4
+		/// address which does not fit in 32 bits.
 		InvalidIpAddress,
 
+		/// --- Thrown when a loopback address (127.0.0.0/8, ::1) is passed to subscribe.
+		LoopbackIpAddress,
+
+		/// --- Thrown when a private address (10.0.0.0/8, 172.16.0.0/12, 192.168.0.0/16)
+		/// is passed to subscribe.
+		PrivateIpAddress,
+
+		/// --- Thrown when a carrier-grade NAT address (100.64.0.0/10) is passed to subscribe.
+		SharedIpAddress,
+
+		/// --- Thrown when a link-local address (169.254.0.0/16, fe80::/10) is passed to subscribe.
+		LinkLocalIpAddress,
+
+		/// --- Thrown when a multicast address (224.0.0.0/4, ff00::/8) is passed to subscribe.
+		MulticastIpAddress,
+
+		/// --- Thrown when a documentation or test-net address (192.0.2.0/24, 198.51.100.0/24,
+		/// 203.0.113.0/24, 198.18.0.0/15, 2001:db8::/32) is passed to subscribe.
+		DocumentationIpAddress,
+
+		/// --- Thrown when a reserved address (240.0.0.0/4, 192.0.0.0/24) is passed to subscribe.
+		ReservedIpAddress,
+
+		/// --- Thrown when an ipv6 unique local address (fc00::/7) is passed to subscribe.
+		UniqueLocalIpAddress,
+
+		/// --- Thrown when an ipv4-mapped ipv6 address (::ffff:0:0/96) is passed to subscribe.
+		Ipv4MappedIpAddress,
+
+		/// --- Thrown when an ipv4 address in the "this network" range (0.0.0.0/8) is
+		/// passed to subscribe.
+		ThisNetworkIpAddress,
+
+		/// --- Thrown when the ip address falls in a range denied by root.
+		IpAddressDenied,
+
+		/// --- Thrown when root passes an ip range with an invalid ip type or a prefix
+		/// length which exceeds the length of the address.
+		InvalidIpRange,
+
+		/// --- Thrown when root adds an ip range override while MaxIpRangeOverrides
+		/// overrides are already set.
+		TooManyIpRangeOverrides,
+
+		/// --- Thrown when an endpoint (ip, port) is already served by another neuron.
+		EndpointInUse,
+
+		/// --- Thrown when MaxUidsPerIp neurons are already subscribed with the ip address.
+		TooManyUidsPerIp,
+
 		/// --- Thrown when an invalid modality attempted on subscribe.
-		/// Currently the chain only accepts modality TEXT = 0.
+		/// The modality must be present in the Modalities registry.
 		InvalidModality,
 
+		/// --- Thrown when root adds a modality code which is already registered.
+		ModalityAlreadyExists,
+
 		/// --- Thrown when subscriptions this block have exeeded the number of 
 		/// allowed.
 		ToManySubscriptionsThisBlock,
 
+		/// --- Thrown when endpoint updates this block have exceeded the number of
+		/// allowed updates.
+		TooManyUpdatesThisBlock,
+
+		/// --- Thrown when a neuron updates its endpoint before the update cooldown
+		/// has passed since its last subscription or update.
+		EndpointUpdateTooFast,
+
+		/// --- Thrown when a neuron adds an endpoint but already serves
+		/// MaxEndpointsPerNeuron endpoints.
+		TooManyEndpoints,
+
+		/// --- Thrown when a neuron adds an endpoint with the same ip, port
+		/// and protocol as one of its existing endpoints.
+		DuplicateEndpoint,
+
+		/// --- Thrown when a neuron removes an endpoint slot which does not exist.
+		InvalidEndpointIndex,
+
+		/// --- Thrown when a neuron removes slot 0, the endpoint passed on subscription.
+		/// This endpoint can only be changed by resubscribing.
+		PrimaryEndpointNotRemovable,
+
+		/// --- Thrown when the metagraph has reached MaxNeurons and every neuron
+		/// is still within its immunity period, so none can be replaced.
+		MetagraphFull,
+
 		/// ---- Thrown when the caller attempts to set the weight keys
 		/// and values but these vectors have different size.
 		WeightVecNotEqualSize,
@@ -230,6 +714,50 @@ decl_error! {
 		/// does not exist in the metagraph.
 		InvalidUid,
 
+		/// ---- Thrown when the caller attempts to set more than MaxWeightsPerNeuron weights.
+		TooManyWeights,
+
+		/// ---- Thrown when a neuron sets its weights again before the weights rate
+		/// limit has passed.
+		SettingWeightsTooFast,
+
+		/// ---- Thrown when the caller sets weights which are all zero. Such weights can
+		/// not be normalized to sum to u32::MAX.
+		WeightsSumToZero,
+
+		/// ---- Thrown when the normalized self weight exceeds MaxSelfWeight.
+		SelfWeightTooHigh,
+
+		/// ---- Thrown when the weights have fewer than MinNonSelfWeights edges to
+		/// other neurons.
+		NotEnoughNonSelfWeights,
+
+		/// ---- Thrown when a normalized weight exceeds MaxEdgeWeight.
+		EdgeWeightTooHigh,
+
+		/// ---- Thrown when a neuron reveals weights without a pending weights commit.
+		NoWeightsCommit,
+
+		/// ---- Thrown when a neuron reveals weights after the reveal window of its
+		/// commit has passed.
+		WeightsCommitExpired,
+
+		/// ---- Thrown when the revealed weights and salt do not hash to the committed hash.
+		InvalidWeightsReveal,
+
+		/// ---- Thrown when the weights were computed against another metagraph version
+		/// than the current one.
+		StaleMetagraph,
+
+		/// ---- Thrown when the weights key is already authorized for a neuron.
+		WeightsKeyInUse,
+
+		/// ---- Thrown when the weights key is the hotkey of an active neuron.
+		WeightsKeyIsHotkey,
+
+		/// ---- Thrown when revoking the weights key of a neuron which has none.
+		NoWeightsKey,
+
 		/// ---- Thrown when the caller triggers an emit but the computed amount
 		/// to emit is zero.
 		NothingToEmit,
@@ -247,6 +775,25 @@ decl_error! {
 		/// See: fn add_stake and fn remove_stake.
 		NonAssociatedColdKey,
 
+		/// ---- Thrown when a coldkey calls a coldkey-only function on a neuron before
+		/// it has accepted the association with the neuron's hotkey. See: fn accept_hotkey.
+		ColdkeyNotConfirmed,
+
+		/// ---- Thrown when a coldkey accepts a hotkey it has already accepted.
+		ColdkeyAlreadyConfirmed,
+
+		/// ---- Thrown when a neuron registers an all zero transport key.
+		InvalidTransportKey,
+
+		/// ---- Thrown when an identity field is longer than MaxIdentityFieldLength.
+		IdentityFieldTooLong,
+
+		/// ---- Thrown when the coldkey can not reserve the identity deposit.
+		NotEnoughBalanceForIdentityDeposit,
+
+		/// ---- Thrown when the identity of a neuron without identity is cleared.
+		NoIdentity,
+
 		/// ---- Thrown when the caller requests removing more stake then there exists 
 		/// in the staking account. See: fn remove_stake.
 		NotEnoughStaketoWithdraw,
@@ -266,180 +813,1029 @@ decl_error! {
 }
 
 
-impl<T: Trait> Printable for Error<T> {
-    fn print(&self) {
-        match self {
-            Error::AlreadyActive => "The node with the supplied public key is already active".print(),
-            Error::NotActive => "The node with the supplied public key is not active".print(),
-            Error::NothingToEmit => "There is nothing to emit".print(),
-            Error::WeightVecNotEqualSize => "The vec of keys and the vec of values are not of the same size".print(),
-            Error::NonAssociatedColdKey => "The used cold key is not associated with the hot key acccount".print(),
-            _ => "Invalid Error Case".print(),
-        }
-    }
-}
+impl<T: Trait> Printable for Error<T> {
+    fn print(&self) {
+        match self {
+            Error::AlreadyActive => "The node with the supplied public key is already active".print(),
+            Error::NotActive => "The node with the supplied public key is not active".print(),
+            Error::NothingToEmit => "There is nothing to emit".print(),
+            Error::WeightVecNotEqualSize => "The vec of keys and the vec of values are not of the same size".print(),
+            Error::NonAssociatedColdKey => "The used cold key is not associated with the hot key acccount".print(),
+            _ => "Invalid Error Case".print(),
+        }
+    }
+}
+
+// ---- Subtensor Dispatchable functions.
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		// Errors must be initialized if they are used by the pallet.
+		type Error = Error<T>;
+		// type Balance = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+
+		// Events must be initialized if they are used by the pallet.
+		fn deposit_event() = default;
+
+		const TransactionByteFee: BalanceOf<T> = T::TransactionByteFee::get();
+
+		/// The maximum number of neurons in the metagraph.
+		const MaxNeurons: u64 = T::MaxNeurons::get();
+
+		/// The number of blocks a new neuron is protected from being replaced.
+		const ImmunityPeriod: T::BlockNumber = T::ImmunityPeriod::get();
+
+		/// The default maximum number of new neurons which can subscribe in a single block.
+		const MaxRegistrationsPerBlock: u32 = T::MaxRegistrationsPerBlock::get();
+
+		/// The default maximum number of endpoint updates in a single block.
+		const MaxUpdatesPerBlock: u32 = T::MaxUpdatesPerBlock::get();
+
+		/// The default number of blocks between two endpoint updates of a neuron.
+		const UpdateCooldown: T::BlockNumber = T::UpdateCooldown::get();
+
+		/// The number of idle blocks after which a neuron is marked inactive.
+		const InactivityThreshold: T::BlockNumber = T::InactivityThreshold::get();
+
+		/// The maximum number of endpoints a neuron can register.
+		const MaxEndpointsPerNeuron: u32 = T::MaxEndpointsPerNeuron::get();
+
+		/// The maximum number of root controlled ip range overrides.
+		const MaxIpRangeOverrides: u32 = T::MaxIpRangeOverrides::get();
+
+		/// The deposit reserved from the coldkey while a neuron has an identity.
+		const IdentityDeposit: BalanceOf<T> = T::IdentityDeposit::get();
+
+		/// The maximum length in bytes of each neuron identity field.
+		const MaxIdentityFieldLength: u32 = T::MaxIdentityFieldLength::get();
+
+		/// The maximum number of outward edges in the weights of a neuron.
+		const MaxWeightsPerNeuron: u32 = T::MaxWeightsPerNeuron::get();
+
+		/// The maximum number of weight rows checked for stale edges each block.
+		const MaxWeightPrunesPerBlock: u64 = T::MaxWeightPrunesPerBlock::get();
+
+		/// The default number of blocks between two weight updates of a neuron.
+		const WeightsSetRateLimit: T::BlockNumber = T::WeightsSetRateLimit::get();
+
+		/// The number of blocks after a weights commit during which it can be revealed.
+		const WeightsRevealWindow: T::BlockNumber = T::WeightsRevealWindow::get();
+
+
+		/// --- Sets the caller weights for the incentive mechanism. The call can be
+		/// made from the hotkey account so is potentially insecure, however, the damage
+		/// of changing weights is minimal if caught early. This function includes all the
+		/// checks that the passed weights meet the requirements. Stored as u64s they represent
+		/// rational values in the range [0,1] which sum to 1 and can be interpreted as
+		/// probabilities. The specific weights determine how inflation propagates outward
+		/// from this peer. Because this function changes the inflation distribution it
+		/// triggers an emit before values are changed on the chain.
+		/// 
+		/// Note: The 32 bit integers weights should represent 1.0 as the max u64.
+		/// However, the function normalizes all integers to u64_max anyway. This means that if the sum of all
+		/// elements is larger or smaller than the amount of elements * u64_max, all elements
+		/// will be corrected for this deviation. 
+		/// 
+		/// # Args:
+		/// 	* `origin`: (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a hotkey who wishes to set their weights.
+		/// 
+		/// 	* `uids` (Vec<u64>):
+		/// 		- The edge endpoint for the weight, i.e. j for w_ij.
+		///
+		/// 	* 'weights' (Vec<u64>):
+		/// 		- The u64 integer encoded weights. Interpreted as rational
+		/// 		values in the range [0,1]. They must sum to in32::MAX.
+		///
+		/// # Emits:
+		/// 	* WeightsSet;
+		/// 		- On successfully setting the weights on chain.
+		///
+		/// # Raises:
+		/// 	* 'WeightVecNotEqualSize':
+		/// 		- If the passed weights and uids have unequal size.
+		///
+		/// 	* 'TooManyWeights':
+		/// 		- If more than MaxWeightsPerNeuron weights are passed.
+		///
+		/// 	* 'SettingWeightsTooFast':
+		/// 		- If the neuron set its weights less than the weights rate limit ago.
+		///
+		/// 	* 'WeightsSumToZero':
+		/// 		- If the weights are not empty and all zero.
+		///
+		/// 	* 'SelfWeightTooHigh':
+		/// 		- If the normalized self weight exceeds MaxSelfWeight.
+		///
+		/// 	* 'NotEnoughNonSelfWeights':
+		/// 		- If fewer than MinNonSelfWeights weights point to other neurons.
+		///
+		/// 	* 'EdgeWeightTooHigh':
+		/// 		- If a normalized weight exceeds MaxEdgeWeight.
+		///
+		/// 	* 'WeightSumToLarge':
+		/// 		- When the calling coldkey is not associated with the hotkey account.
+		///
+		/// 	* 'InsufficientBalance':
+		/// 		- When the amount to stake exceeds the amount of balance in the
+		/// 		associated colkey account.
+		///
+		#[weight = (Module::<T>::max_prune_stale_weights_weight(), DispatchClass::Normal, Pays::No)]
+		pub fn set_weights(origin, dests: Vec<u64>, weights: Vec<u32>) -> dispatch::DispatchResult {
+			Self::do_set_weights(origin, dests, weights, None)
+		}
+
+		/// --- Adds stake to a neuron account. The call is made from the
+		/// coldkey account linked in the neurons's NeuronMetadata.
+		/// Only the associated coldkey is allowed to make staking and
+		/// unstaking requests. This protects the neuron against
+		/// attacks on its hotkey running in production code.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a coldkey signature associated with the hotkey account.
+		///
+		/// 	* 'hotkey' (T::AccountId):
+		/// 		- The hotkey account to add stake to.
+		///
+		/// 	* 'ammount_staked' (u64):
+		/// 		- The ammount to transfer from the balances account of the cold key
+		/// 		into the staking account of the hotkey.
+		///
+		/// # Emits:
+		/// 	* 'StakeAdded':
+		/// 		- On the successful staking of funds.
+		///
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with the hotkey account.
+		///
+		/// 	* 'ColdkeyNotConfirmed':
+		/// 		- When the coldkey has not yet accepted the hotkey through accept_hotkey.
+		///
+		/// 	* 'InsufficientBalance':
+		/// 		- When the amount to stake exceeds the amount of balance in the
+		/// 		associated colkey account.
+		///
+		#[weight = (Module::<T>::max_prune_stale_weights_weight(), DispatchClass::Normal, Pays::Yes)]
+		pub fn add_stake(origin, hotkey: T::AccountId, ammount_staked: u64) -> dispatch::DispatchResult {
+			Self::do_add_stake(origin, hotkey, ammount_staked)
+		}
+
+		/// ---- Remove stake from the staking account. The call must be made
+		/// from the coldkey account attached to the neuron metadata. Only this key
+		/// has permission to make staking and unstaking requests.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a coldkey signature associated with the hotkey account.
+		///
+		/// 	* 'hotkey' (T::AccountId):
+		/// 		- The hotkey account to withdraw stake from.
+		///
+		/// 	* 'ammount_unstaked' (u64):
+		/// 		- The ammount to transfer from the staking account into the balance
+		/// 		of the coldkey.
+		///
+		/// # Emits:
+		/// 	* 'StakeRemoved':
+		/// 		- On successful withdrawl.
+		///
+		/// # Raises:
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with the hotkey account.
+		///
+		/// 	* 'ColdkeyNotConfirmed':
+		/// 		- When the coldkey has not yet accepted the hotkey through accept_hotkey.
+		///
+		/// 	* 'NotEnoughStaketoWithdraw':
+		/// 		- When the amount to unstake exceeds the quantity staked in the
+		/// 		associated hotkey staking account.
+		///
+		#[weight = (Module::<T>::max_prune_stale_weights_weight(), DispatchClass::Normal, Pays::Yes)]
+		pub fn remove_stake(origin, hotkey: T::AccountId, ammount_unstaked: u64) -> dispatch::DispatchResult {
+			Self::do_remove_stake(origin, hotkey, ammount_unstaked)
+		}
+
+		/// ---- Subscribes or updates info for caller with the given metadata. If the caller
+		/// already exists in the active set, the metadata (including the modality) is updated
+		/// but the cold key remains unchanged.
+		/// If the caller does not exist they make a link between this hotkey account
+		/// and the passed coldkey account. Only the cold key has permission to make add_stake/remove_stake calls.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a hotkey associated with the subscribing neuron.
+		///
+		/// 	* 'ip' (u128):
+		/// 		- The u64 encoded IP address of type 6 or 4.
+		///
+		/// 	* 'port' (u16):
+		/// 		- The port number where this neuron receives RPC requests.
+		///
+		/// 	* 'ip_type' (u8):
+		/// 		- The ip type one of (4,6).
+		/// 
+		/// 	* 'modality' (u8):
+		/// 		- The neuron modality type, registered in the Modalities registry.
+		/// 		A neuron can keep its current modality after it has been retired.
+		///
+		/// 	* 'coldkey' (T::AccountId):
+		/// 		- The associated coldkey to be attached to the account.
+		///
+		/// # Emits:
+		/// 	* 'NeuronAdded':
+		/// 		- On subscription of a new neuron to the active set.
+		///
+		/// 	* 'NeuronUpdated':
+		/// 		- On subscription of new metadata attached to the calling hotkey.
+		///
+		/// 	* 'NeuronRemoved':
+		/// 		- When the metagraph is full and the lowest ranked neuron is replaced.
+		///
+		/// # Raises:
+		/// 	* 'InvalidModality':
+		/// 		- When the modality is not in the Modalities registry.
+		///
+		/// 	* 'ToManySubscriptionsThisBlock':
+		/// 		- When the maximum number of new registrations this block is reached.
+		///
+		/// 	* 'TooManyUpdatesThisBlock':
+		/// 		- When the maximum number of endpoint updates this block is reached.
+		///
+		/// 	* 'EndpointUpdateTooFast':
+		/// 		- When the neuron updates its endpoint within the update cooldown.
+		///
+		/// 	* 'MetagraphFull':
+		/// 		- When the metagraph is full and all neurons are within their immunity period.
+		///
+		/// # Weight:
+		/// 	* Vacant uids are reused before a fresh uid is allocated, so uids stay within
+		/// 	0..MaxNeurons. Selecting the neuron to replace in a full metagraph reads the
+		/// 	activity, registration block, stake and last emit of each of these uids, so
+		/// 	the call is weighed as four reads per MaxNeurons, plus a read per ip range
+		/// 	override and pruning the weights of the neuron it emits for.
+		#[weight = (T::DbWeight::get().reads(4 * T::MaxNeurons::get() + T::MaxIpRangeOverrides::get() as u64).saturating_add(Module::<T>::max_prune_stale_weights_weight()), DispatchClass::Normal, Pays::No)]
+		pub fn subscribe(origin, ip: u128, port: u16, ip_type: u8, modality: u8, coldkey: T::AccountId) -> dispatch::DispatchResult {
+			Self::do_subscribe(origin, ip, port, ip_type, modality, coldkey)
+		}
+
+		/// ---- Adds an endpoint to the calling neuron without resubscribing. The endpoint is
+		/// validated with the same ip rules as the subscription endpoint.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a hotkey associated with the neuron.
+		///
+		/// 	* 'ip' (u128), 'port' (u16), 'ip_type' (u8):
+		/// 		- The address of the endpoint.
+		///
+		/// 	* 'protocol' (EndpointProtocol):
+		/// 		- The protocol served on the endpoint.
+		///
+		/// 	* 'version' (u32):
+		/// 		- The version of the protocol served on the endpoint.
+		///
+		/// # Emits:
+		/// 	* 'EndpointAdded':
+		/// 		- On the successful addition of the endpoint.
+		///
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'TooManyEndpoints':
+		/// 		- When the neuron already serves MaxEndpointsPerNeuron endpoints.
+		///
+		/// 	* 'DuplicateEndpoint':
+		/// 		- When the neuron already serves the protocol on the same ip type, ip and port.
+		///
+		/// 	* 'EndpointInUse':
+		/// 		- When another neuron serves an endpoint on the same ip and port.
+		///
+		/// 	* 'EndpointUpdateTooFast':
+		/// 		- When the neuron updates its endpoints within the update cooldown.
+		///
+		/// 	* 'TooManyUpdatesThisBlock':
+		/// 		- When the maximum number of endpoint updates this block is reached.
+		///
+		/// # Weight:
+		/// 	* A read per ip range override, plus the reads of the neuron, its endpoints, the
+		/// 	endpoint index and the update limits, and the writes of the endpoints, the
+		/// 	endpoint index, the update limits and the neuron's last update blocks.
+		#[weight = (T::DbWeight::get().reads_writes(9 + T::MaxIpRangeOverrides::get() as u64, 6), DispatchClass::Normal, Pays::No)]
+		pub fn add_endpoint(origin, ip: u128, port: u16, ip_type: u8, protocol: EndpointProtocol, version: u32) -> dispatch::DispatchResult {
+			Self::do_add_endpoint(origin, ip, port, ip_type, protocol, version)
+		}
+
+		/// ---- Removes an endpoint from the calling neuron. Slot 0 holds the subscription
+		/// endpoint and can not be removed. Endpoints after the removed slot move down one slot.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a hotkey associated with the neuron.
+		///
+		/// 	* 'index' (u32):
+		/// 		- The slot of the endpoint to remove.
+		///
+		/// # Emits:
+		/// 	* 'EndpointRemoved':
+		/// 		- On the successful removal of the endpoint.
+		///
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'PrimaryEndpointNotRemovable':
+		/// 		- When the index is 0.
+		///
+		/// 	* 'InvalidEndpointIndex':
+		/// 		- When the neuron has no endpoint in the slot.
+		///
+		/// 	* 'EndpointUpdateTooFast':
+		/// 		- When the neuron updates its endpoints within the update cooldown.
+		///
+		/// 	* 'TooManyUpdatesThisBlock':
+		/// 		- When the maximum number of endpoint updates this block is reached.
+		///
+		/// # Weight:
+		/// 	* The reads of the neuron, its endpoints, the endpoint index and the update
+		/// 	limits, and the writes of the endpoints, the endpoint index, the update limits
+		/// 	and the neuron's last update blocks.
+		#[weight = (T::DbWeight::get().reads_writes(9, 6), DispatchClass::Normal, Pays::No)]
+		pub fn remove_endpoint(origin, index: u32) -> dispatch::DispatchResult {
+			Self::do_remove_endpoint(origin, index)
+		}
+
+		/// ---- Sets or removes the transport public key of the calling neuron. Peers use the
+		/// key to authenticate connections to the neuron's endpoint.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a hotkey associated with the neuron.
+		///
+		/// 	* 'key' (Option<TransportKey>):
+		/// 		- The new transport key, None removes the key.
+		///
+		/// # Emits:
+		/// 	* 'TransportKeySet':
+		/// 		- On the successful setting of the key.
+		///
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'InvalidTransportKey':
+		/// 		- When the key is all zeros.
+		///
+		/// 	* 'EndpointUpdateTooFast':
+		/// 		- When the neuron updates its endpoints or key within the update cooldown.
+		///
+		/// 	* 'TooManyUpdatesThisBlock':
+		/// 		- When the maximum number of updates this block is reached.
+		///
+		#[weight = (0, DispatchClass::Normal, Pays::No)]
+		pub fn set_transport_key(origin, key: Option<TransportKey>) -> dispatch::DispatchResult {
+			Self::do_set_transport_key(origin, key)
+		}
+
+		/// ---- Removes the neuron associated with the hotkey from the active set. The call
+		/// can be made by the hotkey itself or by the coldkey associated with it. Before the
+		/// neuron is removed, its pending emission is distributed over its weights and its
+		/// remaining stake is returned to the coldkey account.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, the hotkey or the coldkey associated with the neuron.
+		///
+		/// 	* 'hotkey' (T::AccountId):
+		/// 		- The hotkey of the neuron to remove.
+		///
+		/// # Emits:
+		/// 	* 'NeuronRemoved':
+		/// 		- On the successful removal of the neuron.
+		///
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the caller is neither the hotkey nor the associated coldkey.
+		///
+		/// 	* 'ColdkeyNotConfirmed':
+		/// 		- When the caller is the coldkey and it has not yet accepted the hotkey.
+		///
+		#[weight = (Module::<T>::max_prune_stale_weights_weight(), DispatchClass::Normal, Pays::No)]
+		pub fn unsubscribe(origin, hotkey: T::AccountId) -> dispatch::DispatchResult {
+			Self::do_unsubscribe(origin, hotkey)
+		}
+
+		/// ---- Confirms the association between the calling coldkey and a neuron's hotkey.
+		/// A hotkey names its coldkey on subscription without the consent of the coldkey.
+		/// Until the coldkey accepts the hotkey, the neuron is flagged as unconfirmed in its
+		/// NeuronMetadata and the coldkey can not stake, unstake, swap or unsubscribe it.
+		/// Neurons which name their own hotkey as coldkey are confirmed on subscription.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, the coldkey named by the hotkey on subscription.
+		///
+		/// 	* 'hotkey' (T::AccountId):
+		/// 		- The hotkey of the neuron to accept.
+		///
+		/// # Emits:
+		/// 	* 'ColdkeyConfirmed':
+		/// 		- On the successful confirmation of the association.
+		///
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not the coldkey named by the hotkey.
+		///
+		/// 	* 'ColdkeyAlreadyConfirmed':
+		/// 		- When the coldkey has already accepted the hotkey.
+		///
+		#[weight = (0, DispatchClass::Normal, Pays::Yes)]
+		pub fn accept_hotkey(origin, hotkey: T::AccountId) -> dispatch::DispatchResult {
+			Self::do_accept_hotkey(origin, hotkey)
+		}
+
+		/// ---- Replaces the hotkey of a neuron. The call must be made from the coldkey
+		/// account linked in the neuron's NeuronMetadata. The neuron keeps its uid, stake,
+		/// weights and pending emission. This allows a neuron to recover from a
+		/// compromised hotkey without abandoning its uid.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a coldkey signature associated with the hotkey account.
+		///
+		/// 	* 'old_hotkey' (T::AccountId):
+		/// 		- The hotkey currently associated with the neuron.
+		///
+		/// 	* 'new_hotkey' (T::AccountId):
+		/// 		- The hotkey that replaces the old hotkey.
+		///
+		/// # Emits:
+		/// 	* 'HotkeySwapped':
+		/// 		- On the successful replacement of the hotkey.
+		///
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the old hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with the old hotkey account.
+		///
+		/// 	* 'ColdkeyNotConfirmed':
+		/// 		- When the coldkey has not yet accepted the hotkey through accept_hotkey.
+		///
+		/// 	* 'AlreadyActive':
+		/// 		- When the new hotkey is already in the active set.
+		///
+		#[weight = (0, DispatchClass::Normal, Pays::Yes)]
+		pub fn swap_hotkey(origin, old_hotkey: T::AccountId, new_hotkey: T::AccountId) -> dispatch::DispatchResult {
+			Self::do_swap_hotkey(origin, old_hotkey, new_hotkey)
+		}
+
+		/// ---- Sets the identity of a neuron. The call must be made from the confirmed coldkey
+		/// of the neuron. The first identity reserves IdentityDeposit from the coldkey balance,
+		/// later calls replace all fields and keep the deposit.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a coldkey signature associated with the hotkey account.
+		///
+		/// 	* 'hotkey' (T::AccountId):
+		/// 		- The hotkey of the neuron.
+		///
+		/// 	* 'display', 'url', 'contact', 'software_version' (Option<Vec<u8>>):
+		/// 		- The identity fields, None leaves a field empty.
+		///
+		/// # Emits:
+		/// 	* 'IdentitySet':
+		/// 		- On the successful setting of the identity.
+		///
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with the hotkey account.
+		///
+		/// 	* 'ColdkeyNotConfirmed':
+		/// 		- When the coldkey has not yet accepted the hotkey through accept_hotkey.
+		///
+		/// 	* 'IdentityFieldTooLong':
+		/// 		- When a field is longer than MaxIdentityFieldLength.
+		///
+		/// 	* 'NotEnoughBalanceForIdentityDeposit':
+		/// 		- When the coldkey can not reserve the identity deposit.
+		///
+		/// # Weight:
+		/// 	* The reads of the neuron, its identity and the coldkey balance, and the writes
+		/// 	of the identity and the reserved deposit.
+		#[weight = (T::DbWeight::get().reads_writes(4, 2), DispatchClass::Normal, Pays::Yes)]
+		pub fn set_identity(origin, hotkey: T::AccountId, display: Option<Vec<u8>>, url: Option<Vec<u8>>, contact: Option<Vec<u8>>, software_version: Option<Vec<u8>>) -> dispatch::DispatchResult {
+			Self::do_set_identity(origin, hotkey, display, url, contact, software_version)
+		}
+
+		/// ---- Clears the identity of a neuron and returns the deposit to the coldkey.
+		/// The call must be made from the confirmed coldkey of the neuron.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a coldkey signature associated with the hotkey account.
+		///
+		/// 	* 'hotkey' (T::AccountId):
+		/// 		- The hotkey of the neuron.
+		///
+		/// # Emits:
+		/// 	* 'IdentityCleared':
+		/// 		- On the successful removal of the identity.
+		///
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with the hotkey account.
+		///
+		/// 	* 'ColdkeyNotConfirmed':
+		/// 		- When the coldkey has not yet accepted the hotkey through accept_hotkey.
+		///
+		/// 	* 'NoIdentity':
+		/// 		- When the neuron has no identity.
+		///
+		/// # Weight:
+		/// 	* The reads of the neuron, its identity and the coldkey balance, and the writes
+		/// 	of the removed identity and the unreserved deposit.
+		#[weight = (T::DbWeight::get().reads_writes(4, 2), DispatchClass::Normal, Pays::Yes)]
+		pub fn clear_identity(origin, hotkey: T::AccountId) -> dispatch::DispatchResult {
+			Self::do_clear_identity(origin, hotkey)
+		}
+
+		/// ---- Overrides the subscription rate limits set by the Trait constants. Passing None
+		/// for a limit removes its override, so the Trait constant applies again.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- Must be root.
+		///
+		/// 	* 'max_registrations_per_block' (Option<u32>):
+		/// 		- The maximum number of new neurons which can subscribe in a single block.
+		///
+		/// 	* 'max_updates_per_block' (Option<u32>):
+		/// 		- The maximum number of endpoint updates in a single block.
+		///
+		/// 	* 'update_cooldown' (Option<T::BlockNumber>):
+		/// 		- The number of blocks a neuron must wait between two endpoint updates.
+		///
+		/// # Emits:
+		/// 	* 'SubscriptionRateLimitsSet':
+		/// 		- With the limits in effect after the call.
+		///
+		#[weight = (0, DispatchClass::Operational, Pays::No)]
+		pub fn set_subscription_rate_limits(origin, max_registrations_per_block: Option<u32>, max_updates_per_block: Option<u32>, update_cooldown: Option<T::BlockNumber>) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+			MaxRegistrationsPerBlockOverride::set(max_registrations_per_block);
+			MaxUpdatesPerBlockOverride::set(max_updates_per_block);
+			UpdateCooldownOverride::<T>::set(update_cooldown);
+			Self::deposit_event(RawEvent::SubscriptionRateLimitsSet(
+				Self::get_max_registrations_per_block(),
+				Self::get_max_updates_per_block(),
+				Self::get_update_cooldown()
+			));
+			Ok(())
+		}
+
+		/// ---- Overrides the weights rate limit set by the WeightsSetRateLimit Trait constant.
+		/// Passing None removes the override, so the Trait constant applies again.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- Must be root.
+		///
+		/// 	* 'rate_limit' (Option<T::BlockNumber>):
+		/// 		- The number of blocks a neuron must wait between two weight updates.
+		///
+		/// # Emits:
+		/// 	* 'WeightsSetRateLimitSet':
+		/// 		- With the rate limit in effect after the call.
+		///
+		#[weight = (0, DispatchClass::Operational, Pays::No)]
+		pub fn set_weights_rate_limit(origin, rate_limit: Option<T::BlockNumber>) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+			WeightsSetRateLimitOverride::<T>::set(rate_limit);
+			Self::deposit_event(RawEvent::WeightsSetRateLimitSet(Self::get_weights_set_rate_limit()));
+			Ok(())
+		}
+
+		/// ---- Adds a modality to the registry of modalities neurons can subscribe with.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- Must be root.
+		///
+		/// 	* 'modality' (u8):
+		/// 		- The modality code, e.g. IMAGE = 1.
+		///
+		/// 	* 'name' (Vec<u8>):
+		/// 		- The human readable name of the modality, e.g. b"IMAGE".
+		///
+		/// # Emits:
+		/// 	* 'ModalityAdded':
+		/// 		- On the successful registration of the modality.
+		///
+		/// # Raises:
+		/// 	* 'ModalityAlreadyExists':
+		/// 		- When the modality code is already registered.
+		///
+		#[weight = (0, DispatchClass::Operational, Pays::No)]
+		pub fn add_modality(origin, modality: u8, name: Vec<u8>) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!Self::is_valid_modality(modality), Error::<T>::ModalityAlreadyExists);
+			Modalities::insert(modality, &name);
+			Self::deposit_event(RawEvent::ModalityAdded(modality, name));
+			Ok(())
+		}
 
-// ---- Subtensor Dispatchable functions.
-decl_module! {
-	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-		// Errors must be initialized if they are used by the pallet.
-		type Error = Error<T>;
-		// type Balance = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+		/// ---- Retires a modality from the registry. New neurons can no longer subscribe
+		/// with the modality, neurons already subscribed with it keep their modality.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- Must be root.
+		///
+		/// 	* 'modality' (u8):
+		/// 		- The modality code to retire.
+		///
+		/// # Emits:
+		/// 	* 'ModalityRetired':
+		/// 		- On the successful retirement of the modality.
+		///
+		/// # Raises:
+		/// 	* 'InvalidModality':
+		/// 		- When the modality code is not registered.
+		///
+		#[weight = (0, DispatchClass::Operational, Pays::No)]
+		pub fn retire_modality(origin, modality: u8) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+			ensure!(Self::is_valid_modality(modality), Error::<T>::InvalidModality);
+			Modalities::remove(modality);
+			Self::deposit_event(RawEvent::ModalityRetired(modality));
+			Ok(())
+		}
 
+		/// ---- Sets an override on the subscription ip address policy. Addresses within the
+		/// range are either allowed, even if they fall in a special-purpose range such as
+		/// loopback, or denied. Denied ranges take precedence over allowed ranges. This allows
+		/// dev chains to run neurons on loopback or private networks.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- Must be root.
+		///
+		/// 	* 'ip_type' (u8):
+		/// 		- The ip type of the range, 4 for ipv4 and 6 for ipv6.
+		///
+		/// 	* 'network' (u128):
+		/// 		- The u128 encoded network address of the range.
+		///
+		/// 	* 'prefix_len' (u8):
+		/// 		- The number of leading bits of the network address that make up the range.
+		///
+		/// 	* 'allow' (bool):
+		/// 		- True to allow addresses in the range, false to deny them.
+		///
+		/// # Emits:
+		/// 	* 'IpRangeOverrideSet':
+		/// 		- On the successful setting of the override.
+		///
+		/// # Raises:
+		/// 	* 'InvalidIpRange':
+		/// 		- When the ip type is invalid or the prefix length exceeds the address length.
+		///
+		/// 	* 'TooManyIpRangeOverrides':
+		/// 		- When the range is new and MaxIpRangeOverrides overrides are already set.
+		///
+		#[weight = (0, DispatchClass::Operational, Pays::No)]
+		pub fn set_ip_range_override(origin, ip_type: u8, network: u128, prefix_len: u8, allow: bool) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+			ensure!(subscribing::is_valid_ip_range(ip_type, prefix_len), Error::<T>::InvalidIpRange);
+			if !IpRangeOverrides::contains_key((ip_type, network, prefix_len)) {
+				ensure!(IpRangeOverrideCount::get() < T::MaxIpRangeOverrides::get(), Error::<T>::TooManyIpRangeOverrides);
+				IpRangeOverrideCount::mutate(|count| *count += 1);
+			}
+			IpRangeOverrides::insert((ip_type, network, prefix_len), allow);
+			Self::deposit_event(RawEvent::IpRangeOverrideSet(ip_type, network, prefix_len, allow));
+			Ok(())
+		}
 
-		// Events must be initialized if they are used by the pallet.
-		fn deposit_event() = default;
+		/// ---- Removes an override from the subscription ip address policy.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- Must be root.
+		///
+		/// 	* 'ip_type' (u8), 'network' (u128), 'prefix_len' (u8):
+		/// 		- The range of the override, as passed to set_ip_range_override.
+		///
+		/// # Emits:
+		/// 	* 'IpRangeOverrideRemoved':
+		/// 		- On the successful removal of the override.
+		///
+		#[weight = (0, DispatchClass::Operational, Pays::No)]
+		pub fn remove_ip_range_override(origin, ip_type: u8, network: u128, prefix_len: u8) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+			if IpRangeOverrides::contains_key((ip_type, network, prefix_len)) {
+				IpRangeOverrideCount::mutate(|count| *count -= 1);
+			}
+			IpRangeOverrides::remove((ip_type, network, prefix_len));
+			Self::deposit_event(RawEvent::IpRangeOverrideRemoved(ip_type, network, prefix_len));
+			Ok(())
+		}
 
-		const TransactionByteFee: BalanceOf<T> = T::TransactionByteFee::get();
+		/// ---- Sets the maximum number of neurons which can subscribe with the same ip address.
+		/// Neurons already above the limit are kept, the limit applies to new subscriptions and
+		/// to neurons moving to the address.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- Must be root.
+		///
+		/// 	* 'max_uids' (u32):
+		/// 		- The maximum number of neurons per ip address, 0 for unlimited.
+		///
+		/// # Emits:
+		/// 	* 'MaxUidsPerIpSet':
+		/// 		- On the successful setting of the limit.
+		///
+		#[weight = (0, DispatchClass::Operational, Pays::No)]
+		pub fn set_max_uids_per_ip(origin, max_uids: u32) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+			MaxUidsPerIp::put(max_uids);
+			Self::deposit_event(RawEvent::MaxUidsPerIpSet(max_uids));
+			Ok(())
+		}
 
+		/// ---- Sets the limits on the normalized weights checked in set_weights. Weights are
+		/// normalized to u32::MAX, so u32::MAX disables a maximum. Weights already set are
+		/// kept, the limits apply to the next weights a neuron sets.
+		///
+		/// # Args:
+		/// 	* 'origin': (<T as frame_system::Trait>Origin):
+		/// 		- Must be root.
+		///
+		/// 	* 'max_self_weight' (u32):
+		/// 		- The maximum weight a neuron can set on itself.
+		///
+		/// 	* 'min_non_self_weights' (u32):
+		/// 		- The minimum number of edges to other neurons.
+		///
+		/// 	* 'max_edge_weight' (u32):
+		/// 		- The maximum weight a neuron can set on any single edge.
+		///
+		/// # Emits:
+		/// 	* 'WeightLimitsSet':
+		/// 		- On the successful setting of the limits.
+		///
+		#[weight = (0, DispatchClass::Operational, Pays::No)]
+		pub fn set_weight_limits(origin, max_self_weight: u32, min_non_self_weights: u32, max_edge_weight: u32) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+			MaxSelfWeight::put(max_self_weight);
+			MinNonSelfWeights::put(min_non_self_weights);
+			MaxEdgeWeight::put(max_edge_weight);
+			Self::deposit_event(RawEvent::WeightLimitsSet(max_self_weight, min_non_self_weights, max_edge_weight));
+			Ok(())
+		}
 
-		/// --- Sets the caller weights for the incentive mechanism. The call can be
-		/// made from the hotkey account so is potentially insecure, however, the damage
-		/// of changing weights is minimal if caught early. This function includes all the
-		/// checks that the passed weights meet the requirements. Stored as u64s they represent
-		/// rational values in the range [0,1] which sum to 1 and can be interpreted as
-		/// probabilities. The specific weights determine how inflation propagates outward
-		/// from this peer. Because this function changes the inflation distribution it
-		/// triggers an emit before values are changed on the chain.
-		/// 
-		/// Note: The 32 bit integers weights should represent 1.0 as the max u64.
-		/// However, the function normalizes all integers to u64_max anyway. This means that if the sum of all
-		/// elements is larger or smaller than the amount of elements * u64_max, all elements
-		/// will be corrected for this deviation. 
-		/// 
+		/// --- Commits the caller to the weights it will reveal through reveal_weights.
+		/// The weights stay private until they are revealed, so other neurons can not
+		/// copy them. A new commit replaces the pending commit of the caller.
+		///
 		/// # Args:
 		/// 	* `origin`: (<T as frame_system::Trait>Origin):
 		/// 		- The caller, a hotkey who wishes to set their weights.
-		/// 
+		///
+		/// 	* 'commit_hash' (T::Hash):
+		/// 		- The hash of the hotkey, uids, weights and salt to reveal.
+		/// 		See fn get_weights_commit_hash.
+		///
+		/// # Emits:
+		/// 	* WeightsCommitted;
+		/// 		- On successfully committing the hash.
+		///
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
+		/// 	* 'SettingWeightsTooFast':
+		/// 		- If the neuron set its weights, or made a commit it did not reveal,
+		/// 		within the weights rate limit.
+		///
+		#[weight = (0, DispatchClass::Normal, Pays::No)]
+		pub fn commit_weights(origin, commit_hash: T::Hash) -> dispatch::DispatchResult {
+			Self::do_commit_weights(origin, commit_hash)
+		}
+
+		/// --- Reveals the weights committed through commit_weights and sets them with
+		/// the same checks as set_weights. The reveal must be made within
+		/// WeightsRevealWindow blocks of the commit.
+		///
+		/// # Args:
+		/// 	* `origin`: (<T as frame_system::Trait>Origin):
+		/// 		- The caller, the hotkey who committed the weights.
+		///
 		/// 	* `uids` (Vec<u64>):
 		/// 		- The edge endpoint for the weight, i.e. j for w_ij.
 		///
-		/// 	* 'weights' (Vec<u64>):
-		/// 		- The u64 integer encoded weights. Interpreted as rational
-		/// 		values in the range [0,1]. They must sum to in32::MAX.
+		/// 	* 'weights' (Vec<u32>):
+		/// 		- The u32 integer encoded weights.
+		///
+		/// 	* 'salt' (Vec<u8>):
+		/// 		- The salt hashed into the commit.
+		///
+		/// 	* 'version' (Option<u64>):
+		/// 		- The metagraph version the weights were computed against, see
+		/// 		set_weights_versioned. It is not part of the commit hash.
 		///
 		/// # Emits:
 		/// 	* WeightsSet;
 		/// 		- On successfully setting the weights on chain.
 		///
 		/// # Raises:
-		/// 	* 'WeightVecNotEqualSize':
-		/// 		- If the passed weights and uids have unequal size.
+		/// 	* 'NoWeightsCommit':
+		/// 		- If the caller has no pending commit.
 		///
-		/// 	* 'WeightSumToLarge':
-		/// 		- When the calling coldkey is not associated with the hotkey account.
+		/// 	* 'WeightsCommitExpired':
+		/// 		- If the reveal window of the commit has passed.
 		///
-		/// 	* 'InsufficientBalance':
-		/// 		- When the amount to stake exceeds the amount of balance in the
-		/// 		associated colkey account.
+		/// 	* 'InvalidWeightsReveal':
+		/// 		- If the uids, weights and salt do not match the committed hash.
 		///
-		#[weight = (0, DispatchClass::Normal, Pays::No)]
-		pub fn set_weights(origin, dests: Vec<u64>, weights: Vec<u32>) -> dispatch::DispatchResult {
-			Self::do_set_weights(origin, dests, weights)
+		/// 	* 'StaleMetagraph':
+		/// 		- If the passed version does not match the current metagraph version.
+		///
+		#[weight = (Module::<T>::max_prune_stale_weights_weight(), DispatchClass::Normal, Pays::No)]
+		pub fn reveal_weights(origin, dests: Vec<u64>, weights: Vec<u32>, salt: Vec<u8>, version: Option<u64>) -> dispatch::DispatchResult {
+			Self::do_reveal_weights(origin, dests, weights, salt, version)
 		}
 
-		/// --- Adds stake to a neuron account. The call is made from the
-		/// coldkey account linked in the neurons's NeuronMetadata.
-		/// Only the associated coldkey is allowed to make staking and
-		/// unstaking requests. This protects the neuron against
-		/// attacks on its hotkey running in production code.
+		/// --- Changes individual edges of the caller weights without resubmitting the
+		/// whole vector. Each change inserts an edge, changes its value or, with value 0,
+		/// removes it. The resulting weights are re-normalized and go through the same
+		/// checks as set_weights.
 		///
 		/// # Args:
-		/// 	* 'origin': (<T as frame_system::Trait>Origin):
-		/// 		- The caller, a coldkey signature associated with the hotkey account.
+		/// 	* `origin`: (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a hotkey who wishes to update their weights.
 		///
-		/// 	* 'hotkey' (T::AccountId):
-		/// 		- The hotkey account to add stake to.
+		/// 	* `changes` (Vec<(u64, u32)>):
+		/// 		- The (uid, weight) edges to change. Weights are on the scale of the
+		/// 		stored weights, which sum to u32::MAX.
 		///
-		/// 	* 'ammount_staked' (u64):
-		/// 		- The ammount to transfer from the balances account of the cold key
-		/// 		into the staking account of the hotkey.
+		/// 	* 'version' (Option<u64>):
+		/// 		- The metagraph version the changes were computed against, see
+		/// 		set_weights_versioned.
 		///
 		/// # Emits:
-		/// 	* 'StakeAdded':
-		/// 		- On the successful staking of funds.
+		/// 	* WeightsSet;
+		/// 		- On successfully updating the weights on chain.
 		///
 		/// # Raises:
-		/// 	* 'NotActive':
-		/// 		- If the hotkey account is not active (has not subscribed)
+		/// 	* 'TooManyWeights':
+		/// 		- If more than MaxWeightsPerNeuron edges are changed.
 		///
-		/// 	* 'NonAssociatedColdKey':
-		/// 		- When the calling coldkey is not associated with the hotkey account.
+		/// 	* 'DuplicateUids':
+		/// 		- If a uid is changed more than once.
 		///
-		/// 	* 'InsufficientBalance':
-		/// 		- When the amount to stake exceeds the amount of balance in the
-		/// 		associated colkey account.
+		/// 	* 'StaleMetagraph':
+		/// 		- If the passed version does not match the current metagraph version.
 		///
-		#[weight = (0, DispatchClass::Normal, Pays::Yes)]
-		pub fn add_stake(origin, hotkey: T::AccountId, ammount_staked: u64) -> dispatch::DispatchResult {
-			Self::do_add_stake(origin, hotkey, ammount_staked)
+		/// 	* Any error of set_weights for the resulting weights.
+		///
+		#[weight = (Module::<T>::max_prune_stale_weights_weight(), DispatchClass::Normal, Pays::No)]
+		pub fn update_weights(origin, changes: Vec<(u64, u32)>, version: Option<u64>) -> dispatch::DispatchResult {
+			Self::do_update_weights(origin, changes, version)
 		}
 
-		/// ---- Remove stake from the staking account. The call must be made
-		/// from the coldkey account attached to the neuron metadata. Only this key
-		/// has permission to make staking and unstaking requests.
+		/// --- Sets the caller weights as set_weights does, but only if they were computed
+		/// against the current metagraph. Clients pass the MetagraphVersion they read, which
+		/// is bumped whenever a neuron is added to or removed from the metagraph, so weights
+		/// pointing at a uid which has since changed hands are rejected.
+		///
+		/// # Args:
+		/// 	* `origin`: (<T as frame_system::Trait>Origin):
+		/// 		- The caller, a hotkey who wishes to set their weights.
+		///
+		/// 	* `uids` (Vec<u64>):
+		/// 		- The edge endpoint for the weight, i.e. j for w_ij.
+		///
+		/// 	* 'weights' (Vec<u32>):
+		/// 		- The u32 integer encoded weights.
+		///
+		/// 	* 'version' (u64):
+		/// 		- The metagraph version the weights were computed against.
+		///
+		/// # Emits:
+		/// 	* WeightsSet;
+		/// 		- On successfully setting the weights on chain.
+		///
+		/// # Raises:
+		/// 	* 'StaleMetagraph':
+		/// 		- If the passed version does not match the current metagraph version.
+		///
+		/// 	* Any error of set_weights.
+		///
+		#[weight = (Module::<T>::max_prune_stale_weights_weight(), DispatchClass::Normal, Pays::No)]
+		pub fn set_weights_versioned(origin, dests: Vec<u64>, weights: Vec<u32>, version: u64) -> dispatch::DispatchResult {
+			Self::do_set_weights(origin, dests, weights, Some(version))
+		}
+
+		/// ---- Authorizes a weights key to call set_weights and update_weights on behalf
+		/// of a neuron, so the hotkey does not have to be kept on the machine computing the
+		/// weights. The weights key can not make any other call for the neuron. The call
+		/// must be made from the coldkey linked in the neuron's NeuronMetadata, and replaces
+		/// the previous weights key of the neuron. Setting the current weights key again
+		/// is a no-op. The weights key is revoked when it subscribes or is swapped in as a
+		/// hotkey.
 		///
 		/// # Args:
 		/// 	* 'origin': (<T as frame_system::Trait>Origin):
-		/// 		- The caller, a coldkey signature associated with the hotkey account.
+		/// 		- The caller, the coldkey of the neuron.
 		///
 		/// 	* 'hotkey' (T::AccountId):
-		/// 		- The hotkey account to withdraw stake from.
+		/// 		- The hotkey of the neuron.
 		///
-		/// 	* 'ammount_unstaked' (u64):
-		/// 		- The ammount to transfer from the staking account into the balance
-		/// 		of the coldkey.
+		/// 	* 'weights_key' (T::AccountId):
+		/// 		- The account allowed to set the weights of the neuron.
 		///
 		/// # Emits:
-		/// 	* 'StakeRemoved':
-		/// 		- On successful withdrawl.
+		/// 	* 'WeightsKeySet':
+		/// 		- On successfully authorizing the weights key.
 		///
 		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
+		///
 		/// 	* 'NonAssociatedColdKey':
 		/// 		- When the calling coldkey is not associated with the hotkey account.
 		///
-		/// 	* 'NotEnoughStaketoWithdraw':
-		/// 		- When the amount to unstake exceeds the quantity staked in the
-		/// 		associated hotkey staking account.
+		/// 	* 'ColdkeyNotConfirmed':
+		/// 		- When the coldkey has not yet accepted the hotkey.
 		///
-		#[weight = (0, DispatchClass::Normal, Pays::Yes)]
-		pub fn remove_stake(origin, hotkey: T::AccountId, ammount_unstaked: u64) -> dispatch::DispatchResult {
-			Self::do_remove_stake(origin, hotkey, ammount_unstaked)
+		/// 	* 'WeightsKeyIsHotkey':
+		/// 		- When the weights key is the hotkey of an active neuron.
+		///
+		/// 	* 'WeightsKeyInUse':
+		/// 		- When the weights key is already authorized for a neuron.
+		///
+		/// # Weight:
+		/// 	* The reads of the neuron, the activity and owner of the weights key and the
+		/// 	neuron's previous key, and the writes of both key indexes and the removal of
+		/// 	the previous key.
+		#[weight = (T::DbWeight::get().reads_writes(6, 3), DispatchClass::Normal, Pays::Yes)]
+		pub fn set_weights_key(origin, hotkey: T::AccountId, weights_key: T::AccountId) -> dispatch::DispatchResult {
+			Self::do_set_weights_key(origin, hotkey, weights_key)
 		}
 
-		/// ---- Subscribes or updates info for caller with the given metadata. If the caller
-		/// already exists in the active set, the metadata is updated but the cold key remains unchanged.
-		/// If the caller does not exist they make a link between this hotkey account
-		/// and the passed coldkey account. Only the cold key has permission to make add_stake/remove_stake calls.
+		/// ---- Revokes the weights key of a neuron. The call must be made from the
+		/// coldkey linked in the neuron's NeuronMetadata.
 		///
 		/// # Args:
 		/// 	* 'origin': (<T as frame_system::Trait>Origin):
-		/// 		- The caller, a hotkey associated with the subscribing neuron.
+		/// 		- The caller, the coldkey of the neuron.
 		///
-		/// 	* 'ip' (u128):
-		/// 		- The u64 encoded IP address of type 6 or 4.
+		/// 	* 'hotkey' (T::AccountId):
+		/// 		- The hotkey of the neuron.
 		///
-		/// 	* 'port' (u16):
-		/// 		- The port number where this neuron receives RPC requests.
+		/// # Emits:
+		/// 	* 'WeightsKeyRevoked':
+		/// 		- On successfully revoking the weights key.
 		///
-		/// 	* 'ip_type' (u8):
-		/// 		- The ip type one of (4,6).
-		/// 
-		/// 	* 'modality' (u8):
-		/// 		- The neuron modality type.
+		/// # Raises:
+		/// 	* 'NotActive':
+		/// 		- If the hotkey account is not active (has not subscribed)
 		///
-		/// 	* 'coldkey' (T::AccountId):
-		/// 		- The associated coldkey to be attached to the account.
+		/// 	* 'NonAssociatedColdKey':
+		/// 		- When the calling coldkey is not associated with the hotkey account.
 		///
-		/// # Emits:
-		/// 	* 'NeuronAdded':
-		/// 		- On subscription of a new neuron to the active set.
+		/// 	* 'ColdkeyNotConfirmed':
+		/// 		- When the coldkey has not yet accepted the hotkey.
 		///
-		/// 	* 'NeuronUpdated':
-		/// 		- On subscription of new metadata attached to the calling hotkey.
-		#[weight = (0, DispatchClass::Normal, Pays::No)]
-		pub fn subscribe(origin, ip: u128, port: u16, ip_type: u8, modality: u8, coldkey: T::AccountId) -> dispatch::DispatchResult {
-			Self::do_subscribe(origin, ip, port, ip_type, modality, coldkey)
+		/// 	* 'NoWeightsKey':
+		/// 		- When the neuron has no weights key.
+		///
+		/// # Weight:
+		/// 	* The reads of the neuron and its weights key, and the removal of both key indexes.
+		#[weight = (T::DbWeight::get().reads_writes(4, 2), DispatchClass::Normal, Pays::Yes)]
+		pub fn revoke_weights_key(origin, hotkey: T::AccountId) -> dispatch::DispatchResult {
+			Self::do_revoke_weights_key(origin, hotkey)
 		}
 
 		/// ---- Finalizes the block by updating pallet state. This function is not publicly available
@@ -454,14 +1850,23 @@ decl_module! {
 			// Self::do_finalize(n);
 		}
 
+		/// ---- Migrates the pallet storage to the latest layout when the runtime is upgraded.
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_to_v2() + Self::migrate_to_v3() + Self::migrate_to_v4()
+		}
+
 		/// ---- Called on the initialization of this pallet. (the order of on_finalize calls is determined in the runtime)
+		/// Moves the transaction fee pool into the block reward, sweeps a bounded number of uids
+		/// for inactivity and stale weights and accrues the block reward per unit of stake.
 		///
 		/// # Args:
 		/// 	* 'n': (T::BlockNumber):
 		/// 		- The number of the block we are initializing.
 		fn on_initialize(n: T::BlockNumber) -> Weight {
 		    Self::move_transaction_fee_pool_to_block_reward();
-			Self::update_pending_emissions()
+			let sweep_weight = Self::sweep_inactive_neurons();
+			let prune_weight = Self::sweep_stale_weights();
+			Self::update_pending_emissions() + sweep_weight + prune_weight
 		}
 	}
 }
@@ -572,6 +1977,10 @@ impl Default for CallType {
 
 type TransactionFee = u64;
 
+/// ---- The custom InvalidTransaction code of set_weights calls rejected by the
+/// weights rate limit.
+pub const SETTING_WEIGHTS_TOO_FAST: u8 = 1;
+
 
 
 impl<T: Trait> Module<T> where
@@ -624,6 +2033,36 @@ impl<T: Trait + Send + Sync> ChargeTransactionPayment<T> where
         Self(Default::default())
     }
 
+    /// Rejects set_weights calls of an active neuron which set its weights less than the
+    /// weights rate limit ago, so they do not enter the transaction pool.
+    pub fn check_weights_rate_limit(who: &T::AccountId) -> Result<(), TransactionValidityError> {
+        if !Module::<T>::is_hotkey_active(who) {
+            return Ok(());
+        }
+
+        let uid = Module::<T>::get_uid_for_hotkey(who);
+        if Module::<T>::weights_rate_limit_has_passed(uid) {
+            Ok(())
+        } else {
+            Err(InvalidTransaction::Custom(SETTING_WEIGHTS_TOO_FAST).into())
+        }
+    }
+
+    /// Rejects commit_weights calls of an active neuron which may not commit yet, see
+    /// fn weights_commit_rate_limit_has_passed.
+    pub fn check_weights_commit_rate_limit(who: &T::AccountId) -> Result<(), TransactionValidityError> {
+        if !Module::<T>::is_hotkey_active(who) {
+            return Ok(());
+        }
+
+        let uid = Module::<T>::get_uid_for_hotkey(who);
+        if Module::<T>::weights_commit_rate_limit_has_passed(uid) {
+            Ok(())
+        } else {
+            Err(InvalidTransaction::Custom(SETTING_WEIGHTS_TOO_FAST).into())
+        }
+    }
+
     pub fn can_pay_set_weights(who: &T::AccountId) -> Result<TransactionFee, TransactionValidityError> {
         let transaction_fee = Module::<T>::get_transaction_fee_for_emission(who);
         Ok(transaction_fee)
@@ -717,14 +2156,25 @@ impl<T: Trait + Send + Sync> SignedExtension for ChargeTransactionPayment<T>
         len: usize,
     ) -> TransactionValidity {
         match call.is_sub_type() {
-            Some(Call::set_weights(..)) => {
-                let transaction_fee = Self::can_pay_set_weights(who)?;
+            Some(Call::set_weights(..)) | Some(Call::set_weights_versioned(..)) | Some(Call::reveal_weights(..)) | Some(Call::update_weights(..)) => {
+                // A weights key pays from the self-emission of the neuron it acts for.
+                let hotkey_id = Module::<T>::get_hotkey_for_weights_caller(who);
+                Self::check_weights_rate_limit(&hotkey_id)?;
+                let transaction_fee = Self::can_pay_set_weights(&hotkey_id)?;
                 Ok(ValidTransaction {
                     priority: Self::get_priority_set_weights(transaction_fee, len as u64),
                     longevity: 1,
                     ..Default::default()
                 })
             }
+            Some(Call::commit_weights(..)) => {
+                Self::check_weights_commit_rate_limit(who)?;
+                let _transaction_fee = Self::can_pay_other(info, who, len as u64)?;
+                Ok(ValidTransaction {
+                    priority: Self::get_priority_vanilla(),
+                    ..Default::default()
+                })
+            }
             Some(Call::add_stake(..)) => {
                 let _transaction_fee = Self::can_pay_add_stake(who, len as u64)?;
                 Ok(ValidTransaction {
@@ -768,12 +2218,20 @@ impl<T: Trait + Send + Sync> SignedExtension for ChargeTransactionPayment<T>
         debug::info!("PRE DISPATCH: Transaction length: {:?}", len);
 
         match call.is_sub_type() {
-            Some(Call::set_weights(..)) => {
+            Some(Call::set_weights(..)) | Some(Call::set_weights_versioned(..)) | Some(Call::reveal_weights(..)) | Some(Call::update_weights(..)) => {
                 // To pay for the set_weights operation, the self_weight of a neuron is used for payment
                 // This can be >= 0, however the lower the self weight, the lower the priority in the block
                 // and may result the transaction is not put into a block
-                let transaction_fee = Self::can_pay_set_weights(who)?;
-                Ok((CallType::SetWeights, transaction_fee, who.clone())) // 0 indicates that post_dispatch should use the self-weight to pay for the transaction
+                // A weights key pays from the self-emission of the neuron it acts for.
+                let hotkey_id = Module::<T>::get_hotkey_for_weights_caller(who);
+                Self::check_weights_rate_limit(&hotkey_id)?;
+                let transaction_fee = Self::can_pay_set_weights(&hotkey_id)?;
+                Ok((CallType::SetWeights, transaction_fee, hotkey_id)) // 0 indicates that post_dispatch should use the self-weight to pay for the transaction
+            }
+            Some(Call::commit_weights(..)) => {
+                Self::check_weights_commit_rate_limit(who)?;
+                let transaction_fee = Self::can_pay_other(info, who, len as u64)?;
+                Ok((CallType::Other, transaction_fee, who.clone()))
             }
             Some(Call::add_stake(..)) => {
                 // The transaction fee for the add_stake function is paid from the coldkey balance
@@ -819,6 +2277,7 @@ impl<T: Trait + Send + Sync> SignedExtension for ChargeTransactionPayment<T>
                 match call_type {
                     CallType::SetWeights => {
                         // account_id = hotkey_id, since this method is called with the hotkey
+                        // or with a weights key, which pre_dispatch resolves to its hotkey
                         let uid = Module::<T>::get_uid_for_hotkey(&account_id);
                         Module::<T>::remove_stake_from_neuron_hotkey_account(uid, transaction_fee);
                         Module::<T>::update_transaction_fee_pool(transaction_fee);
@@ -0,0 +1,80 @@
+use super::*;
+
+impl<T: Trait> Module<T> {
+    /// Marks neurons which have not performed a hotkey action for InactivityThreshold blocks
+    /// as inactive. Inactive neurons stop accruing pending emission until they subscribe again.
+    ///
+    /// The sweep is bounded: every block at most MaxLivenessChecksPerBlock uids are checked,
+    /// starting at the uid where the previous sweep stopped. Over consecutive blocks the
+    /// sweep cycles through the full metagraph.
+    ///
+    /// # Returns
+    /// 	* weight (Weight):
+    /// 		- The database weight of the uids checked and of the neurons seeded or
+    /// 		marked inactive.
+    ///
+    pub fn sweep_inactive_neurons() -> Weight {
+        let neuron_count = Self::get_neuron_count();
+        if neuron_count == 0 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        let threshold: T::BlockNumber = T::InactivityThreshold::get();
+        let checks = sp_std::cmp::min(T::MaxLivenessChecksPerBlock::get(), neuron_count);
+
+        // --- The neuron count and cursor are read, the cursor written.
+        let mut weight: Weight = T::DbWeight::get().reads_writes(2, 1);
+        let mut uid = LivenessSweepCursor::get() % neuron_count;
+        for _ in 0..checks {
+            // --- The activity, inactive flag and last update of the uid are read.
+            weight = weight.saturating_add(T::DbWeight::get().reads(3));
+            if Self::is_uid_active(uid) && !Self::is_neuron_inactive(uid) {
+                if !LastUpdate::<T>::contains_key(uid) {
+                    // --- Neurons which subscribed before liveness tracking existed
+                    // start their inactivity period now.
+                    LastUpdate::<T>::insert(uid, current_block);
+                    weight = weight.saturating_add(T::DbWeight::get().writes(1));
+                } else if Self::get_last_update_for_neuron(uid) + threshold < current_block {
+                    // --- Settling reads the stake, flag, accumulator, checkpoint and pending
+                    // emission and writes the last two, the inactive flag is written.
+                    Self::settle_pending_emission_for_neuron(uid);
+                    InactiveNeurons::insert(uid, true);
+                    weight = weight.saturating_add(T::DbWeight::get().reads_writes(5, 3));
+                    Self::deposit_event(RawEvent::NeuronInactive(uid));
+                }
+            }
+            uid = (uid + 1) % neuron_count;
+        }
+        LivenessSweepCursor::put(uid);
+
+        weight
+    }
+
+    /// Records that the hotkey of the neuron performed an action in the current block.
+    ///
+    pub fn update_last_update_for_neuron(uid: u64) {
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        LastUpdate::<T>::insert(uid, current_block);
+    }
+
+    /// Returns the block number at which the hotkey of the neuron last performed an action.
+    /// Returns 0 when the uid does not exist.
+    ///
+    pub fn get_last_update_for_neuron(uid: u64) -> T::BlockNumber {
+        return LastUpdate::<T>::get(uid);
+    }
+
+    /// Returns true if the neuron has been marked inactive by the liveness sweep.
+    ///
+    pub fn is_neuron_inactive(uid: u64) -> bool {
+        return InactiveNeurons::get(uid);
+    }
+
+    /// Clears the inactive flag of the neuron, so it accrues pending emission again.
+    ///
+    pub fn reactivate_neuron(uid: u64) {
+        Self::settle_pending_emission_for_neuron(uid);
+        InactiveNeurons::remove(uid);
+    }
+}
@@ -0,0 +1,163 @@
+use super::*;
+use frame_support::storage::migration::{remove_storage_prefix, put_storage_value, get_storage_value, StorageIterator};
+use sp_std::cell::Cell;
+
+// ---- The NeuronMetadata layout stored by runtimes before Releases::V2_0_0.
+#[derive(Encode, Decode)]
+struct NeuronMetadataV1<AccountId> {
+    ip: u128,
+    port: u16,
+    ip_type: u8,
+    uid: u64,
+    modality: u8,
+    hotkey: AccountId,
+    coldkey: AccountId,
+}
+
+// ---- The NeuronMetadata layout stored by runtimes at Releases::V2_0_0.
+#[derive(Encode, Decode)]
+struct NeuronMetadataV2<AccountId> {
+    #[allow(dead_code)]
+    version: u8,
+    ip: u128,
+    port: u16,
+    ip_type: u8,
+    uid: u64,
+    modality: u8,
+    hotkey: AccountId,
+    coldkey: AccountId,
+    coldkey_confirmed: bool,
+}
+
+impl<T: Trait> Module<T> {
+    /// Migrates the storage from Releases::V1_0_0 to Releases::V2_0_0. Does nothing when
+    /// the storage is already migrated.
+    ///
+    /// Legacy NeuronMetadata entries are translated into the versioned layout. Their coldkeys
+    /// staked before accept_hotkey existed, so the association is kept as confirmed. The
+    /// coldkey, modality, endpoint and endpoint uid storage is built for every translated
+    /// neuron. Legacy neurons sharing an endpoint are kept, the index points at the last one.
+    /// The modality registry is seeded with TEXT, the only modality the legacy runtime
+    /// accepted, and the storage of the replaced subscription rate limiter is cleared.
+    ///
+    /// # Returns
+    /// 	* weight (Weight):
+    /// 		- The database weight of the reads and writes made by the migration.
+    ///
+    pub fn migrate_to_v2() -> Weight {
+        if StorageVersion::get() != Releases::V1_0_0 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        // --- Neurons only decodes the latest layout, so the entries are translated
+        // through their raw keys and the storage of each neuron is built on the way.
+        let mut translated: u64 = 0;
+        for (key, old) in StorageIterator::<NeuronMetadataV1<T::AccountId>>::new(b"SubtensorModule", b"Neurons") {
+            translated += 1;
+            let uid = old.uid;
+            Self::link_neuron_to_coldkey(uid, &old.coldkey);
+            ModalityUids::insert(old.modality, uid, ());
+            Self::index_endpoint(uid, old.ip_type, old.ip, old.port);
+            if !Endpoints::contains_key(uid) {
+                Self::set_primary_endpoint_for_neuron(uid, old.ip, old.port, old.ip_type);
+            }
+            put_storage_value(b"SubtensorModule", b"Neurons", &key, NeuronMetadataV2 {
+                version: 1,
+                ip: old.ip,
+                port: old.port,
+                ip_type: old.ip_type,
+                uid: uid,
+                modality: old.modality,
+                hotkey: old.hotkey,
+                coldkey: old.coldkey,
+                coldkey_confirmed: true,
+            });
+        }
+
+        if Modalities::iter().next().is_none() {
+            Modalities::insert(0, b"TEXT".to_vec());
+        }
+
+        remove_storage_prefix(b"SubtensorModule", b"SubscriptionsThisBlock", &[]);
+        remove_storage_prefix(b"SubtensorModule", b"LastSubscriptionBlock", &[]);
+
+        StorageVersion::put(Releases::V2_0_0);
+
+        // --- Per neuron: the translation, the stake read and the coldkey, total stake,
+        // modality, endpoint and endpoint index writes.
+        T::DbWeight::get().reads_writes(3 + 4 * translated, 5 + 8 * translated)
+    }
+
+    /// Migrates the storage from Releases::V2_0_0 to Releases::V3_0_0. Does nothing when
+    /// the storage is already migrated. Adds an empty transport key to every neuron.
+    ///
+    /// # Returns
+    /// 	* weight (Weight):
+    /// 		- The database weight of the reads and writes made by the migration.
+    ///
+    pub fn migrate_to_v3() -> Weight {
+        if StorageVersion::get() != Releases::V2_0_0 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let translated = Cell::new(0u64);
+        Neurons::<T>::translate::<NeuronMetadataV2<T::AccountId>, _>(|_uid, old| {
+            translated.set(translated.get() + 1);
+            Some(NeuronMetadataOf::<T> {
+                version: NEURON_METADATA_VERSION,
+                ip: old.ip,
+                port: old.port,
+                ip_type: old.ip_type,
+                uid: old.uid,
+                modality: old.modality,
+                hotkey: old.hotkey,
+                coldkey: old.coldkey,
+                coldkey_confirmed: old.coldkey_confirmed,
+                transport_key: None,
+            })
+        });
+
+        StorageVersion::put(Releases::V3_0_0);
+        T::DbWeight::get().reads_writes(1 + translated.get(), 1 + translated.get())
+    }
+
+    /// Migrates the storage from Releases::V3_0_0 to Releases::V4_0_0. Does nothing when
+    /// the storage is already migrated.
+    ///
+    /// The parallel WeightUids and WeightVals maps are merged into Weights. Uids without a
+    /// matching value, left behind when the two maps drifted apart, are dropped. Rows longer
+    /// than MaxWeightsPerNeuron are kept until the neuron sets its weights again.
+    ///
+    /// # Returns
+    /// 	* weight (Weight):
+    /// 		- The database weight of the reads and writes made by the migration.
+    ///
+    pub fn migrate_to_v4() -> Weight {
+        if StorageVersion::get() != Releases::V3_0_0 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let mut translated: u64 = 0;
+        for (key, uids) in StorageIterator::<Vec<u64>>::new(b"SubtensorModule", b"WeightUids") {
+            translated += 1;
+            // --- Both maps use the identity hasher, so the key is the encoded source uid.
+            let source_uid = match u64::decode(&mut &key[..]) {
+                Ok(uid) => uid,
+                Err(_) => continue,
+            };
+            let values = get_storage_value::<Vec<u32>>(b"SubtensorModule", b"WeightVals", &key).unwrap_or_default();
+            let mut weights: Vec<(u64, u32)> = uids.into_iter().zip(values.into_iter()).collect();
+            weights.sort_by_key(|(uid, _)| *uid);
+            Weights::insert(source_uid, weights);
+        }
+
+        remove_storage_prefix(b"SubtensorModule", b"WeightUids", &[]);
+        remove_storage_prefix(b"SubtensorModule", b"WeightVals", &[]);
+
+        StorageVersion::put(Releases::V4_0_0);
+
+        // --- Per neuron: the reads of both rows, the write of the merged row and the
+        // removal of both rows.
+        T::DbWeight::get().reads_writes(1 + 2 * translated, 1 + 3 * translated)
+    }
+}
@@ -22,6 +22,7 @@ impl<T: Trait> Module<T> {
         // ---- We check that the NeuronMetadata is linked to the calling
         // cold key, otherwise throw a NonAssociatedColdKey error.
         ensure!(Self::neuron_belongs_to_coldkey(&neuron, &coldkey), Error::<T>::NonAssociatedColdKey);
+        ensure!(neuron.coldkey_confirmed, Error::<T>::ColdkeyNotConfirmed);
 
         // --- We call the emit function for the associated hotkey. Neurons must call an emit before they change 
         // their stake or else can cheat the system by adding stake just before
@@ -59,6 +60,7 @@ impl<T: Trait> Module<T> {
     /// It throws the following errors if there is something wrong
     /// - NotActive : The suplied hotkey is not in use. This ususally means a node that uses this key has not subscribed yet, or has unsubscribed
     /// - NonAssociatedColdKey : The supplied hotkey account id is not subscribed using the supplied cold key
+    /// - ColdkeyNotConfirmed : The cold key has not accepted the hotkey through accept_hotkey
     /// - NotEnoughStaketoWithdraw : The ammount of stake available in the hotkey account is lower than the requested amount
     /// - CouldNotConvertToBalance : A conversion error occured while converting stake from u64 to Balance
     ///
@@ -80,6 +82,7 @@ impl<T: Trait> Module<T> {
         // ---- We check that the NeuronMetadata is linked to the calling
         // cold key, otherwise throw a NonAssociatedColdKey error.
         ensure!(Self::neuron_belongs_to_coldkey(&neuron, &coldkey), Error::<T>::NonAssociatedColdKey);
+        ensure!(neuron.coldkey_confirmed, Error::<T>::ColdkeyNotConfirmed);
 
         // --- We call the emit function for the associated hotkey.
         // Neurons must call an emit before they remove
@@ -142,6 +145,31 @@ impl<T: Trait> Module<T> {
         TotalStake::put(total_stake - decrement);
     }
 
+    /// Returns the sum of the stake of all neurons owned by the coldkey.
+    ///
+    pub fn get_total_stake_for_coldkey(coldkey: &T::AccountId) -> u64 {
+        return TotalColdkeyStake::<T>::get(coldkey);
+    }
+
+    /// Increases the stake aggregate of the coldkey owning the neuron. Neurons which
+    /// are not confirmed by their coldkey do not count towards its aggregate.
+    ///
+    pub fn increase_coldkey_stake_for_neuron(uid: u64, increment: u64) {
+        let neuron = Self::get_neuron_for_uid(uid);
+        if neuron.coldkey_confirmed {
+            TotalColdkeyStake::<T>::mutate(&neuron.coldkey, |stake| *stake += increment);
+        }
+    }
+
+    /// Decreases the stake aggregate of the coldkey owning the neuron.
+    ///
+    pub fn decrease_coldkey_stake_for_neuron(uid: u64, decrement: u64) {
+        let neuron = Self::get_neuron_for_uid(uid);
+        if neuron.coldkey_confirmed {
+            TotalColdkeyStake::<T>::mutate(&neuron.coldkey, |stake| *stake = stake.saturating_sub(decrement));
+        }
+    }
+
     /// Increases the amount of stake in a neuron's hotkey account by the amount provided
     /// The uid parameter identifies the neuron holding the hotkey account
     ///
@@ -155,6 +183,8 @@ impl<T: Trait> Module<T> {
     pub fn add_stake_to_neuron_hotkey_account(uid: u64, amount: u64) {
         assert!(Self::is_uid_active(uid));
 
+        // --- The emission accrued with the previous stake is settled first.
+        Self::settle_pending_emission_for_neuron(uid);
         let prev_stake: u64 = Stake::get(uid);
 
         // This should never happen. If a user has this ridiculous amount of stake,
@@ -166,6 +196,7 @@ impl<T: Trait> Module<T> {
         Stake::insert(uid, new_stake);
 
         Self::increase_total_stake(amount);
+        Self::increase_coldkey_stake_for_neuron(uid, amount);
     }
 
     /// Decreases the amount of stake in a neuron's hotkey account by the amount provided
@@ -181,6 +212,8 @@ impl<T: Trait> Module<T> {
     pub fn remove_stake_from_neuron_hotkey_account(uid: u64, amount: u64) {
         assert!(Self::is_uid_active(uid));
 
+        // --- The emission accrued with the previous stake is settled first.
+        Self::settle_pending_emission_for_neuron(uid);
         let hotkey_stake: u64 = Stake::get(uid);
 
         // By this point, there should be enough stake in the hotkey account for this to work.
@@ -189,6 +222,7 @@ impl<T: Trait> Module<T> {
         Stake::insert(uid, hotkey_stake - amount);
 
         Self::decrease_total_stake(amount);
+        Self::decrease_coldkey_stake_for_neuron(uid, amount);
     }
 
     /// This adds stake (balance) to a cold key account. It takes the account id of the coldkey account and a Balance as parameters.
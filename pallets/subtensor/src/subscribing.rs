@@ -10,15 +10,33 @@ impl<T: Trait> Module<T> {
         ensure!(Self::specified_coldkey_is_linked_to_hotkey_if_active(&hotkey_id, &coldkey), Error::<T>::NonAssociatedColdKey);
 
         // --- We make validy checks on the passed data.
-        ensure!(is_valid_modality(modality), Error::<T>::InvalidModality);
+        ensure!(Self::is_valid_modality(modality) || Self::is_current_modality_of_hotkey(&hotkey_id, modality), Error::<T>::InvalidModality);
         ensure!(is_valid_ip_type(ip_type), Error::<T>::InvalidIpType);
-        ensure!(is_valid_ip_address(ip_type, ip), Error::<T>::InvalidIpAddress);
-        ensure!(Self::check_and_increment_subscriptions_per_block(), Error::<T>::ToManySubscriptionsThisBlock);
+        Self::ensure_ip_address_allowed(ip_type, ip)?;
 
         // --- We switch here between an update and a subscribe.
         if !Self::is_hotkey_active(&hotkey_id) {
-            // --- We get the next available subscription uid.
-            let uid = Self::get_next_uid();
+            // --- We check that the registration limit of this block has not been reached.
+            ensure!(Self::registrations_this_block_available(), Error::<T>::ToManySubscriptionsThisBlock);
+
+            // --- We peek at the uid for the new neuron. When the metagraph is full
+            // this is the uid of the lowest ranked neuron, which is replaced below.
+            let uid = Self::get_uid_for_new_neuron()?;
+
+            // --- We check that no other neuron serves the endpoint. The neuron being
+            // replaced gives up its endpoints, so they are available to the new neuron.
+            Self::ensure_endpoint_available(uid, ip_type, ip, port)?;
+
+            // --- We unsubscribe the neuron currently holding the uid, if any.
+            Self::replace_neuron_at_uid(uid)?;
+
+            // --- The uid is taken and the registration only counts towards the limit
+            // once every fallible step has passed.
+            Self::claim_uid_for_new_neuron(uid);
+            Self::increment_registrations_this_block();
+
+            // --- A weights key which subscribes as a hotkey stops acting for its neuron.
+            Self::revoke_weights_key_of_account(&hotkey_id);
 
             // -- We add this hotkey to the active set.
             Self::add_hotkey_to_active_set(&hotkey_id, uid);
@@ -30,6 +48,10 @@ impl<T: Trait> Module<T> {
             // -- We initialize table values for this peer.
             Self::create_hotkey_account(neuron.uid);
             Self::update_last_emit_for_neuron(neuron.uid);
+            Self::update_block_at_registration_for_neuron(neuron.uid);
+            Self::update_last_endpoint_update_for_neuron(neuron.uid);
+            Self::set_primary_endpoint_for_neuron(neuron.uid, ip, port, ip_type);
+            Self::update_last_update_for_neuron(neuron.uid);
             Self::init_weight_matrix_for_neuron(&neuron);
 
             // --- We deposit the neuron added event.
@@ -38,9 +60,22 @@ impl<T: Trait> Module<T> {
             // --- We get the uid associated with this hotkey account.
             let uid = Self::get_uid_for_hotkey(&hotkey_id);
 
+            // --- We check that no other neuron serves the endpoint.
+            Self::ensure_endpoint_available(uid, ip_type, ip, port)?;
+
+            // --- We check the update cooldown of the neuron and the update limit of this block.
+            ensure!(Self::update_cooldown_has_passed(uid), Error::<T>::EndpointUpdateTooFast);
+            ensure!(Self::check_and_increment_updates_per_block(), Error::<T>::TooManyUpdatesThisBlock);
+
             // --- If the neuron is already subscribed, we allow an update to their
             // modality and ip.
-            let neuron = Self::update_neuron_in_metagraph(uid, ip, port, ip_type);
+            let neuron = Self::update_neuron_in_metagraph(uid, ip, port, ip_type, modality);
+            Self::update_last_endpoint_update_for_neuron(uid);
+            Self::set_primary_endpoint_for_neuron(uid, ip, port, ip_type);
+
+            // --- The subscription proves the neuron is alive.
+            Self::update_last_update_for_neuron(uid);
+            Self::reactivate_neuron(uid);
 
             // --- We call the emit for the resubscribe.
             Self::emit_for_neuron( &neuron );
@@ -51,10 +86,268 @@ impl<T: Trait> Module<T> {
         Ok(())
     }
 
+    /// Removes a neuron from the metagraph. The call can be signed either by the neuron's hotkey
+    /// or by the coldkey it is associated with. Before the neuron is removed its pending emission
+    /// is distributed over its weights and all remaining stake is returned to the coldkey.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The supplied hotkey is not in the active set.
+    /// - NonAssociatedColdKey : The caller is neither the hotkey nor its associated coldkey.
+    /// - ColdkeyNotConfirmed : The caller is the coldkey and it has not accepted the hotkey.
+    /// - CouldNotConvertToBalance : The remaining stake could not be converted to a Balance.
+    ///
+    pub fn do_unsubscribe(origin: T::Origin, hotkey: T::AccountId) -> dispatch::DispatchResult {
+
+        // --- We check the callers (hotkey or coldkey) signature.
+        let caller = ensure_signed(origin)?;
+
+        // --- We check that the hotkey is in the active set.
+        ensure!(Self::is_hotkey_active(&hotkey), Error::<T>::NotActive);
+        let neuron = Self::get_neuron_for_hotkey(&hotkey);
+
+        // --- We check that the caller is either the hotkey or the associated coldkey.
+        ensure!(caller == hotkey || Self::neuron_belongs_to_coldkey(&neuron, &caller), Error::<T>::NonAssociatedColdKey);
+
+        // --- A coldkey can only remove the neuron once it has accepted the hotkey.
+        ensure!(caller == hotkey || neuron.coldkey_confirmed, Error::<T>::ColdkeyNotConfirmed);
+
+        // --- We flush the pending emission, return the stake and clear all storage.
+        Self::unsubscribe_neuron(&neuron)?;
+
+        // --- We deposit the neuron removed event.
+        Self::deposit_event(RawEvent::NeuronRemoved(neuron.uid));
+        Ok(())
+    }
+
+    /// Replaces the hotkey of a neuron with a new hotkey. The call must be signed by the coldkey
+    /// associated with the neuron. The uid, stake, weights and pending emission of the neuron
+    /// are preserved, only the hotkey which controls the neuron changes. A pending weights
+    /// commit hashes the old hotkey and can not be revealed by the new one, so it is cleared.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The old hotkey is not in the active set.
+    /// - NonAssociatedColdKey : The caller is not the coldkey associated with the old hotkey.
+    /// - ColdkeyNotConfirmed : The coldkey has not accepted the old hotkey.
+    /// - AlreadyActive : The new hotkey is already in the active set.
+    ///
+    pub fn do_swap_hotkey(origin: T::Origin, old_hotkey: T::AccountId, new_hotkey: T::AccountId) -> dispatch::DispatchResult {
+
+        // --- We check the callers (coldkey) signature.
+        let coldkey = ensure_signed(origin)?;
+
+        // --- We check that the old hotkey is active and owned by the calling coldkey.
+        ensure!(Self::is_hotkey_active(&old_hotkey), Error::<T>::NotActive);
+        let neuron = Self::get_neuron_for_hotkey(&old_hotkey);
+        ensure!(Self::neuron_belongs_to_coldkey(&neuron, &coldkey), Error::<T>::NonAssociatedColdKey);
+        ensure!(neuron.coldkey_confirmed, Error::<T>::ColdkeyNotConfirmed);
+
+        // --- We check that the new hotkey is not already used by another neuron.
+        ensure!(!Self::is_hotkey_active(&new_hotkey), Error::<T>::AlreadyActive);
+
+        // --- A weights key which becomes a hotkey stops acting for its neuron.
+        Self::revoke_weights_key_of_account(&new_hotkey);
+
+        // --- We move the uid from the old hotkey to the new hotkey.
+        Active::<T>::remove(&old_hotkey);
+        Self::add_hotkey_to_active_set(&new_hotkey, neuron.uid);
+        Self::update_hotkey_in_metagraph(neuron.uid, new_hotkey.clone());
+        WeightCommits::<T>::remove(neuron.uid);
+
+        // --- We deposit the hotkey swapped event.
+        Self::deposit_event(RawEvent::HotkeySwapped(neuron.uid, old_hotkey, new_hotkey));
+        Ok(())
+    }
+
+    /// Confirms the association between a neuron and the coldkey it named on subscription.
+    /// The call must be signed by that coldkey. Until the association is confirmed the
+    /// coldkey can not stake, unstake, swap the hotkey or unsubscribe the neuron.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The supplied hotkey is not in the active set.
+    /// - NonAssociatedColdKey : The caller is not the coldkey named by the hotkey.
+    /// - ColdkeyAlreadyConfirmed : The association has already been confirmed.
+    ///
+    pub fn do_accept_hotkey(origin: T::Origin, hotkey: T::AccountId) -> dispatch::DispatchResult {
+
+        // --- We check the callers (coldkey) signature.
+        let coldkey = ensure_signed(origin)?;
+
+        // --- We check that the hotkey is active and names the calling coldkey.
+        ensure!(Self::is_hotkey_active(&hotkey), Error::<T>::NotActive);
+        let neuron = Self::get_neuron_for_hotkey(&hotkey);
+        ensure!(Self::neuron_belongs_to_coldkey(&neuron, &coldkey), Error::<T>::NonAssociatedColdKey);
+        ensure!(!neuron.coldkey_confirmed, Error::<T>::ColdkeyAlreadyConfirmed);
+
+        // --- We flag the association as confirmed.
+        Self::confirm_coldkey_for_neuron(neuron.uid);
+
+        // --- We deposit the coldkey confirmed event.
+        Self::deposit_event(RawEvent::ColdkeyConfirmed(neuron.uid, coldkey));
+        Ok(())
+    }
+
     /********************************
      --==[[  Helper functions   ]]==--
     *********************************/
 
+    /// Flushes the pending emission of the neuron, returns all of its stake to the
+    /// associated coldkey and removes every per-uid storage entry.
+    ///
+    pub fn unsubscribe_neuron(neuron: &NeuronMetadataOf<T>) -> dispatch::DispatchResult {
+        // --- The stake returned to the coldkey includes the neuron's own share of its
+        // pending emission, so the conversion is checked against that upper bound
+        // before anything is written.
+        let pending_emission = Self::get_pending_emission_for_neuron(neuron.uid).to_num::<u64>();
+        let max_stake = Self::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid).saturating_add(pending_emission);
+        ensure!(Self::u64_to_balance(max_stake).is_some(), Error::<T>::CouldNotConvertToBalance);
+
+        // --- We emit before the neuron leaves, so its peers receive what the
+        // neuron has accumulated up to this block.
+        Self::emit_for_neuron(neuron);
+
+        // --- We return the remaining stake to the coldkey. This also decreases
+        // the total stake.
+        let stake = Self::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid);
+        if let Some(stake_as_balance) = Self::u64_to_balance(stake) {
+            if stake > 0 {
+                Self::remove_stake_from_neuron_hotkey_account(neuron.uid, stake);
+                Self::add_balance_to_coldkey_account(&neuron.coldkey, stake_as_balance);
+            }
+        }
+
+        Self::remove_neuron_from_metagraph(neuron);
+        Ok(())
+    }
+
+    /// Removes all storage entries associated with the neuron. A check should be made
+    /// that the stake of this neuron has been returned before calling this function,
+    /// otherwise the stake is lost.
+    ///
+    pub fn remove_neuron_from_metagraph(neuron: &NeuronMetadataOf<T>) {
+        Self::unlink_neuron_from_coldkey(neuron);
+        Self::remove_identity_for_neuron(neuron);
+        Active::<T>::remove(&neuron.hotkey);
+        Neurons::<T>::remove(neuron.uid);
+        ModalityUids::remove(neuron.modality, neuron.uid);
+        Self::remove_weight_matrix_for_neuron(neuron);
+        LastWeightsSet::<T>::remove(neuron.uid);
+        WeightCommits::<T>::remove(neuron.uid);
+        Self::remove_weights_key_for_neuron(neuron.uid);
+        LastEmit::<T>::remove(neuron.uid);
+        BlockAtRegistration::<T>::remove(neuron.uid);
+        LastEndpointUpdate::<T>::remove(neuron.uid);
+        VacantUids::mutate(|uids| uids.push(neuron.uid));
+        Self::unindex_endpoints_for_neuron(neuron);
+        Endpoints::remove(neuron.uid);
+        LastUpdate::<T>::remove(neuron.uid);
+        InactiveNeurons::remove(neuron.uid);
+        PendingEmission::remove(neuron.uid);
+        RewardPerStakeCheckpoint::remove(neuron.uid);
+        Stake::remove(neuron.uid);
+
+        // --- The uid can be reused by a new neuron, so the edges of other neurons
+        // pointing at this uid become stale. They no longer route emission and are
+        // pruned by the stale weights sweep.
+        Self::bump_metagraph_version_on_remove(neuron.uid);
+    }
+
+    /// Returns the uid for a new subscription without writing to storage. A vacant uid is
+    /// reused first. Otherwise, while less than MaxNeurons uids were allocated, the next
+    /// fresh uid is returned, and once the metagraph is full the uid of the lowest ranked
+    /// neuron outside its immunity period is handed to the new neuron. The neuron holding
+    /// the uid is unsubscribed by replace_neuron_at_uid and the uid is taken by
+    /// claim_uid_for_new_neuron.
+    ///
+    pub fn get_uid_for_new_neuron() -> Result<u64, dispatch::DispatchError> {
+        if let Some(uid) = VacantUids::get().last() {
+            return Ok(*uid);
+        }
+        if Self::get_neuron_count() < T::MaxNeurons::get() {
+            return Ok(NextUID::get());
+        }
+
+        let uid_to_replace = Self::get_uid_to_replace();
+        ensure!(uid_to_replace.is_some(), Error::<T>::MetagraphFull);
+        Ok(uid_to_replace.unwrap())
+    }
+
+    /// Unsubscribes the neuron holding the uid, so the uid can be given to a new neuron.
+    /// Vacant and fresh uids are left as they are.
+    ///
+    pub fn replace_neuron_at_uid(uid: u64) -> dispatch::DispatchResult {
+        if Self::is_uid_active(uid) {
+            let neuron = Self::get_neuron_for_uid(uid);
+            Self::unsubscribe_neuron(&neuron)?;
+            Self::deposit_event(RawEvent::NeuronRemoved(uid));
+        }
+        Ok(())
+    }
+
+    /// Takes the uid returned by get_uid_for_new_neuron for the new neuron. A fresh uid
+    /// is allocated, any other uid is removed from the vacant uids. Called once every
+    /// fallible subscription step has passed.
+    ///
+    pub fn claim_uid_for_new_neuron(uid: u64) {
+        if uid == NextUID::get() {
+            Self::get_next_uid();
+        } else {
+            VacantUids::mutate(|uids| uids.retain(|vacant_uid| *vacant_uid != uid));
+        }
+    }
+
+    /// Returns the uid that should be given to a new neuron when the metagraph is full.
+    /// The neuron with the lowest stake is selected, where ties are broken by the longest
+    /// time since the last emit. Neurons within their immunity period are never selected.
+    /// Vacant uids are handed out through VacantUids and skipped.
+    ///
+    /// Returns None if every neuron is within its immunity period.
+    ///
+    pub fn get_uid_to_replace() -> Option<u64> {
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+
+        let mut lowest: Option<(u64, u64, T::BlockNumber)> = None; // (uid, stake, last_emit)
+        for uid in 0..Self::get_neuron_count() {
+            if !Self::is_uid_active(uid) {
+                continue;
+            }
+
+            if Self::is_neuron_immune(uid, current_block) {
+                continue;
+            }
+
+            let stake = Self::get_stake_of_neuron_hotkey_account_by_uid(uid);
+            let last_emit = Self::get_last_emit_for_neuron(uid);
+            let is_lower = match lowest {
+                None => true,
+                Some((_, lowest_stake, lowest_last_emit)) => {
+                    stake < lowest_stake || (stake == lowest_stake && last_emit < lowest_last_emit)
+                }
+            };
+            if is_lower {
+                lowest = Some((uid, stake, last_emit));
+            }
+        }
+
+        lowest.map(|(uid, _, _)| uid)
+    }
+
+    /// Returns true if the neuron subscribed less than ImmunityPeriod blocks ago.
+    ///
+    pub fn is_neuron_immune(uid: u64, current_block: T::BlockNumber) -> bool {
+        BlockAtRegistration::<T>::get(uid) + T::ImmunityPeriod::get() > current_block
+    }
+
+    pub fn update_block_at_registration_for_neuron(uid: u64) {
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        BlockAtRegistration::<T>::insert(uid, current_block);
+    }
+
+    /// Returns the block number at which the neuron subscribed. Returns 0 when
+    /// the uid does not exist.
+    pub fn get_block_at_registration_for_neuron(uid: u64) -> T::BlockNumber {
+        return BlockAtRegistration::<T>::get(uid);
+    }
+
     pub fn specified_coldkey_is_linked_to_hotkey_if_active(hotkey : &T::AccountId, coldkey : &T::AccountId) -> bool {
         if !Self::is_hotkey_active(hotkey) {
             return true;
@@ -70,97 +363,401 @@ impl<T: Trait> Module<T> {
         // the account_id is already used. If this is omitted, this assert breaks.
         assert_eq!(Self::is_uid_active(uid), false);
         let metadata = NeuronMetadataOf::<T> {
+            version: NEURON_METADATA_VERSION,
             ip: ip,
             port: port,
             ip_type: ip_type,
             uid: uid,
             modality: modality,
+            coldkey_confirmed: hotkey == coldkey,
+            transport_key: None,
             hotkey: hotkey,
             coldkey: coldkey,
         };
         Neurons::<T>::insert(uid, &metadata);
+        Self::bump_metagraph_version_on_add(uid);
+        ModalityUids::insert(modality, uid, ());
+        Self::index_endpoint(uid, ip_type, ip, port);
+        if metadata.coldkey_confirmed {
+            Self::link_neuron_to_coldkey(uid, &metadata.coldkey);
+        }
         return metadata;
     }
 
-    pub fn update_neuron_in_metagraph(uid: u64, ip: u128, port: u16, ip_type: u8) -> NeuronMetadataOf<T> {
+    pub fn update_neuron_in_metagraph(uid: u64, ip: u128, port: u16, ip_type: u8, modality: u8) -> NeuronMetadataOf<T> {
         // Before calling this function, a check should be made to see if
         // the account_id is already used. If this is omitted, this assert breaks.
         assert_eq!(Self::is_uid_active(uid), true);
         let old_metadata = Self::get_neuron_for_uid(uid);
         let new_metadata = NeuronMetadataOf::<T> {
+            version: NEURON_METADATA_VERSION,
             ip: ip,
             port: port,
             ip_type: ip_type,
-            modality: old_metadata.modality,
+            modality: modality,
             uid: old_metadata.uid,
             hotkey: old_metadata.hotkey,
             coldkey: old_metadata.coldkey,
+            coldkey_confirmed: old_metadata.coldkey_confirmed,
+            transport_key: old_metadata.transport_key,
         };
 
+        if old_metadata.modality != modality {
+            ModalityUids::remove(old_metadata.modality, uid);
+            ModalityUids::insert(modality, uid, ());
+        }
+        // --- The old subscription endpoint stays claimed when an additional endpoint serves it.
+        let additional_endpoints: Vec<NeuronEndpoint> = Self::get_endpoints_for_neuron(uid).into_iter().skip(1).collect();
+        Self::unindex_endpoint(uid, old_metadata.ip_type, old_metadata.ip, old_metadata.port, &additional_endpoints);
+        Self::index_endpoint(uid, ip_type, ip, port);
+
         Neurons::<T>::insert(uid, &new_metadata);
         return new_metadata;
     }
 
-    pub fn check_and_increment_subscriptions_per_block() -> bool {
-        let num_allowed_subscriptions = 25;
-        let current_block: T::BlockNumber = system::Module::<T>::block_number();
-        let last_subscription: T::BlockNumber = LastSubscriptionBlock::<T>::get();
-        if last_subscription < current_block {
-            SubscriptionsThisBlock::put(1);
-            LastSubscriptionBlock::<T>::put(current_block);
-            return true;
+    pub fn update_hotkey_in_metagraph(uid: u64, hotkey: T::AccountId) -> NeuronMetadataOf<T> {
+        // Before calling this function, a check should be made to see if
+        // the uid is active. If this is omitted, this assert breaks.
+        assert_eq!(Self::is_uid_active(uid), true);
+        let mut metadata = Self::get_neuron_for_uid(uid);
+        metadata.hotkey = hotkey;
+
+        Neurons::<T>::insert(uid, &metadata);
+        return metadata;
+    }
+
+    /// Checks that the subscription endpoint is not served by another neuron and, when the
+    /// neuron is new or moves to another ip address, that fewer than MaxUidsPerIp neurons are
+    /// subscribed with the address. Pass the uid the neuron holds or, for a new neuron, the uid
+    /// it receives. The endpoints of a neuron being replaced at that uid count as available.
+    pub fn ensure_endpoint_available(uid: u64, ip_type: u8, ip: u128, port: u16) -> dispatch::DispatchResult {
+        Self::ensure_endpoint_not_in_use(uid, ip_type, ip, port)?;
+
+        let joins_ip = if Self::is_uid_active(uid) {
+            let neuron = Self::get_neuron_for_uid(uid);
+            neuron.ip_type != ip_type || neuron.ip != ip
         } else {
-            let subs_this_block = SubscriptionsThisBlock::get();
-            if subs_this_block >= num_allowed_subscriptions {
-                return false;
-            } else {
-                SubscriptionsThisBlock::put(subs_this_block + 1);
-                LastSubscriptionBlock::<T>::put(current_block);
-                return true;
+            true
+        };
+        let max_uids = MaxUidsPerIp::get();
+        if joins_ip && max_uids > 0 {
+            ensure!(UidsPerIp::get((ip_type, ip)) < max_uids, Error::<T>::TooManyUidsPerIp);
+        }
+        Ok(())
+    }
+
+    /// Checks that no neuron other than uid serves an endpoint on the ip address and port.
+    pub fn ensure_endpoint_not_in_use(uid: u64, ip_type: u8, ip: u128, port: u16) -> dispatch::DispatchResult {
+        if let Some(owner) = EndpointUids::get((ip_type, ip, port)) {
+            ensure!(owner == uid, Error::<T>::EndpointInUse);
+        }
+        Ok(())
+    }
+
+    /// Adds the subscription endpoint of the neuron to EndpointUids and UidsPerIp.
+    pub fn index_endpoint(uid: u64, ip_type: u8, ip: u128, port: u16) {
+        EndpointUids::insert((ip_type, ip, port), uid);
+        UidsPerIp::mutate((ip_type, ip), |count| *count += 1);
+    }
+
+    /// Removes the subscription endpoint of the neuron from UidsPerIp, and from EndpointUids
+    /// unless one of the remaining endpoints of the neuron serves the same ip address and port.
+    pub fn unindex_endpoint(uid: u64, ip_type: u8, ip: u128, port: u16, remaining: &[NeuronEndpoint]) {
+        Self::release_endpoint_address(uid, ip_type, ip, port, remaining);
+        UidsPerIp::mutate_exists((ip_type, ip), |count| {
+            *count = count.map(|c| c.saturating_sub(1)).filter(|c| *c > 0);
+        });
+    }
+
+    /// Claims the ip address and port of an additional endpoint for the neuron in EndpointUids.
+    pub fn claim_endpoint_address(uid: u64, ip_type: u8, ip: u128, port: u16) {
+        EndpointUids::insert((ip_type, ip, port), uid);
+    }
+
+    /// Releases the ip address and port in EndpointUids, unless one of the remaining endpoints
+    /// of the neuron still serves them.
+    pub fn release_endpoint_address(uid: u64, ip_type: u8, ip: u128, port: u16, remaining: &[NeuronEndpoint]) {
+        if remaining.iter().any(|e| e.ip_type == ip_type && e.ip == ip && e.port == port) {
+            return;
+        }
+        if EndpointUids::get((ip_type, ip, port)) == Some(uid) {
+            EndpointUids::remove((ip_type, ip, port));
+        }
+    }
+
+    /// Removes the subscription endpoint and all additional endpoints of the neuron from
+    /// EndpointUids and UidsPerIp.
+    pub fn unindex_endpoints_for_neuron(neuron: &NeuronMetadataOf<T>) {
+        for endpoint in Endpoints::get(neuron.uid).iter().skip(1) {
+            Self::release_endpoint_address(neuron.uid, endpoint.ip_type, endpoint.ip, endpoint.port, &[]);
+        }
+        Self::unindex_endpoint(neuron.uid, neuron.ip_type, neuron.ip, neuron.port, &[]);
+    }
+
+    /// Checks the ip address against the subscription address policy. Addresses in a range
+    /// denied by root are always rejected. Addresses in a range allowed by root are accepted,
+    /// even when they fall in a special-purpose range. All other addresses are classified and
+    /// each rejection reason maps to its own error.
+    pub fn ensure_ip_address_allowed(ip_type: u8, ip: u128) -> dispatch::DispatchResult {
+        let rejection = get_ip_rejection(ip_type, ip);
+
+        // --- Malformed addresses can not be overridden.
+        if rejection == Some(IpRejection::Invalid) {
+            return Err(Error::<T>::InvalidIpAddress.into());
+        }
+
+        // --- We check the root controlled overrides, deny takes precedence over allow.
+        let mut allowed = false;
+        for ((override_type, network, prefix_len), allow) in IpRangeOverrides::iter() {
+            if override_type != ip_type || !is_ip_in_range(ip_type, ip, network, prefix_len) {
+                continue;
             }
+            if !allow {
+                return Err(Error::<T>::IpAddressDenied.into());
+            }
+            allowed = true;
+        }
+        if allowed {
+            return Ok(());
+        }
+
+        match rejection {
+            None => Ok(()),
+            Some(IpRejection::Invalid) => Err(Error::<T>::InvalidIpAddress.into()),
+            Some(IpRejection::Loopback) => Err(Error::<T>::LoopbackIpAddress.into()),
+            Some(IpRejection::Private) => Err(Error::<T>::PrivateIpAddress.into()),
+            Some(IpRejection::Shared) => Err(Error::<T>::SharedIpAddress.into()),
+            Some(IpRejection::LinkLocal) => Err(Error::<T>::LinkLocalIpAddress.into()),
+            Some(IpRejection::Multicast) => Err(Error::<T>::MulticastIpAddress.into()),
+            Some(IpRejection::Documentation) => Err(Error::<T>::DocumentationIpAddress.into()),
+            Some(IpRejection::Reserved) => Err(Error::<T>::ReservedIpAddress.into()),
+            Some(IpRejection::UniqueLocal) => Err(Error::<T>::UniqueLocalIpAddress.into()),
+            Some(IpRejection::Ipv4Mapped) => Err(Error::<T>::Ipv4MappedIpAddress.into()),
+            Some(IpRejection::ThisNetwork) => Err(Error::<T>::ThisNetworkIpAddress.into()),
         }
     }
-}
 
+    pub fn confirm_coldkey_for_neuron(uid: u64) {
+        Neurons::<T>::mutate(uid, |neuron| neuron.coldkey_confirmed = true);
+        Self::link_neuron_to_coldkey(uid, &Self::get_neuron_for_uid(uid).coldkey);
+    }
 
-fn is_valid_modality(modality: u8) -> bool {
-    let allowed_values: Vec<u8> = vec![0];
-    return allowed_values.contains(&modality);
+    /// Adds the neuron to the ColdkeyNeurons index of the coldkey and adds
+    /// its current stake to the TotalColdkeyStake of the coldkey.
+    pub fn link_neuron_to_coldkey(uid: u64, coldkey: &T::AccountId) {
+        ColdkeyNeurons::<T>::insert(coldkey, uid, ());
+        let stake = Self::get_stake_of_neuron_hotkey_account_by_uid(uid);
+        TotalColdkeyStake::<T>::mutate(coldkey, |total| *total += stake);
+    }
+
+    /// Removes a confirmed neuron from the ColdkeyNeurons index of its coldkey and
+    /// subtracts any stake it still holds from the TotalColdkeyStake of the coldkey.
+    pub fn unlink_neuron_from_coldkey(neuron: &NeuronMetadataOf<T>) {
+        if !neuron.coldkey_confirmed {
+            return;
+        }
+        ColdkeyNeurons::<T>::remove(&neuron.coldkey, neuron.uid);
+        let stake = Self::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid);
+        TotalColdkeyStake::<T>::mutate(&neuron.coldkey, |total| *total = total.saturating_sub(stake));
+        if TotalColdkeyStake::<T>::get(&neuron.coldkey) == 0 && Self::get_uids_for_coldkey(&neuron.coldkey).is_empty() {
+            TotalColdkeyStake::<T>::remove(&neuron.coldkey);
+        }
+    }
+
+    /// Returns the uids of all neurons owned by the coldkey.
+    pub fn get_uids_for_coldkey(coldkey: &T::AccountId) -> Vec<u64> {
+        return ColdkeyNeurons::<T>::iter_prefix(coldkey).map(|(uid, _)| uid).collect();
+    }
+
+    /// Returns the metadata of all neurons owned by the coldkey.
+    pub fn get_neurons_for_coldkey(coldkey: &T::AccountId) -> Vec<NeuronMetadataOf<T>> {
+        return Self::get_uids_for_coldkey(coldkey).into_iter().map(|uid| Self::get_neuron_for_uid(uid)).collect();
+    }
+
+    /// Returns true if the modality is present in the Modalities registry.
+    pub fn is_valid_modality(modality: u8) -> bool {
+        return Modalities::contains_key(modality);
+    }
+
+    /// Returns true if the hotkey is active and its neuron is subscribed with the modality.
+    /// Allows neurons to resubscribe with a modality which has since been retired.
+    pub fn is_current_modality_of_hotkey(hotkey: &T::AccountId, modality: u8) -> bool {
+        Self::is_hotkey_active(hotkey) && Self::get_neuron_for_hotkey(hotkey).modality == modality
+    }
+
+    /// Returns the uids of all neurons subscribed with the modality.
+    pub fn get_uids_for_modality(modality: u8) -> Vec<u64> {
+        return ModalityUids::iter_prefix(modality).map(|(uid, _)| uid).collect();
+    }
+
+    pub fn get_max_registrations_per_block() -> u32 {
+        return MaxRegistrationsPerBlockOverride::get().unwrap_or_else(T::MaxRegistrationsPerBlock::get);
+    }
+
+    pub fn get_max_updates_per_block() -> u32 {
+        return MaxUpdatesPerBlockOverride::get().unwrap_or_else(T::MaxUpdatesPerBlock::get);
+    }
+
+    pub fn get_update_cooldown() -> T::BlockNumber {
+        return UpdateCooldownOverride::<T>::get().unwrap_or_else(T::UpdateCooldown::get);
+    }
+
+    pub fn update_last_endpoint_update_for_neuron(uid: u64) {
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        LastEndpointUpdate::<T>::insert(uid, current_block);
+    }
+
+    /// Returns true if the update cooldown has passed since the neuron last subscribed
+    /// or updated its endpoint.
+    pub fn update_cooldown_has_passed(uid: u64) -> bool {
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        LastEndpointUpdate::<T>::get(uid) + Self::get_update_cooldown() <= current_block
+    }
+
+    /// Returns true if the registration limit of the current block has not been reached.
+    pub fn registrations_this_block_available() -> bool {
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        if LastRegistrationBlock::<T>::get() < current_block {
+            return Self::get_max_registrations_per_block() > 0;
+        }
+        return RegistrationsThisBlock::get() < Self::get_max_registrations_per_block();
+    }
+
+    /// Counts a registration towards the limit of the current block.
+    pub fn increment_registrations_this_block() {
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        if LastRegistrationBlock::<T>::get() < current_block {
+            RegistrationsThisBlock::put(0);
+        }
+        RegistrationsThisBlock::mutate(|registrations| *registrations += 1);
+        LastRegistrationBlock::<T>::put(current_block);
+    }
+
+    pub fn check_and_increment_updates_per_block() -> bool {
+        let num_allowed_updates = Self::get_max_updates_per_block();
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        let last_update: T::BlockNumber = LastUpdateBlock::<T>::get();
+        if last_update < current_block {
+            UpdatesThisBlock::put(0);
+        }
+        let updates_this_block = UpdatesThisBlock::get();
+        if updates_this_block >= num_allowed_updates {
+            return false;
+        }
+        UpdatesThisBlock::put(updates_this_block + 1);
+        LastUpdateBlock::<T>::put(current_block);
+        return true;
+    }
 }
 
+
 fn is_valid_ip_type(ip_type: u8) -> bool {
     let allowed_values: Vec<u8> = vec![4, 6];
     return allowed_values.contains(&ip_type);
 }
 
-// @todo (Parallax 2-1-2021) : Implement exclusion of private IP ranges
-fn is_valid_ip_address(ip_type: u8, addr: u128) -> bool {
+// ---- The reasons an ip address is rejected by the subscription address policy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IpRejection {
+    Invalid,
+    Loopback,
+    Private,
+    Shared,
+    LinkLocal,
+    Multicast,
+    Documentation,
+    Reserved,
+    UniqueLocal,
+    Ipv4Mapped,
+    ThisNetwork,
+}
+
+// ---- Special-purpose ipv4 ranges as (network, prefix length, rejection).
+const IPV4_SPECIAL_RANGES: [(u32, u8, IpRejection); 14] = [
+    (0x00000000, 8, IpRejection::ThisNetwork),     // 0.0.0.0/8
+    (0x7f000000, 8, IpRejection::Loopback),        // 127.0.0.0/8
+    (0x0a000000, 8, IpRejection::Private),         // 10.0.0.0/8
+    (0xac100000, 12, IpRejection::Private),        // 172.16.0.0/12
+    (0xc0a80000, 16, IpRejection::Private),        // 192.168.0.0/16
+    (0x64400000, 10, IpRejection::Shared),         // 100.64.0.0/10
+    (0xa9fe0000, 16, IpRejection::LinkLocal),      // 169.254.0.0/16
+    (0xe0000000, 4, IpRejection::Multicast),       // 224.0.0.0/4
+    (0xc0000200, 24, IpRejection::Documentation),  // 192.0.2.0/24
+    (0xc6336400, 24, IpRejection::Documentation),  // 198.51.100.0/24
+    (0xcb007100, 24, IpRejection::Documentation),  // 203.0.113.0/24
+    (0xc6120000, 15, IpRejection::Documentation),  // 198.18.0.0/15
+    (0xf0000000, 4, IpRejection::Reserved),        // 240.0.0.0/4
+    (0xc0000000, 24, IpRejection::Reserved),       // 192.0.0.0/24
+];
+
+// ---- Special-purpose ipv6 ranges as (network, prefix length, rejection).
+const IPV6_SPECIAL_RANGES: [(u128, u8, IpRejection); 6] = [
+    (0x1, 128, IpRejection::Loopback),                                // ::1/128
+    (0xffff_0000_0000, 96, IpRejection::Ipv4Mapped),                  // ::ffff:0:0/96
+    (0xfc00 << 112, 7, IpRejection::UniqueLocal),                     // fc00::/7
+    (0xfe80 << 112, 10, IpRejection::LinkLocal),                      // fe80::/10
+    (0xff00 << 112, 8, IpRejection::Multicast),                       // ff00::/8
+    (0x2001_0db8 << 96, 32, IpRejection::Documentation),              // 2001:db8::/32
+];
+
+fn ip_type_bits(ip_type: u8) -> u8 {
+    if ip_type == 4 { 32 } else { 128 }
+}
+
+// Returns true if the ip type is valid and the prefix length fits in the address.
+pub fn is_valid_ip_range(ip_type: u8, prefix_len: u8) -> bool {
+    is_valid_ip_type(ip_type) && prefix_len <= ip_type_bits(ip_type)
+}
+
+// Returns true if the first prefix_len bits of addr and network are equal.
+fn is_ip_in_range(ip_type: u8, addr: u128, network: u128, prefix_len: u8) -> bool {
+    if prefix_len == 0 {
+        return true;
+    }
+    let shift = (ip_type_bits(ip_type) - prefix_len) as u32;
+    (addr >> shift) == (network >> shift)
+}
+
+// Classifies the ip address against the subscription address policy. Returns None
+// when the address is a valid public address.
+pub fn get_ip_rejection(ip_type: u8, addr: u128) -> Option<IpRejection> {
     if !is_valid_ip_type(ip_type) {
-        return false;
+        return Some(IpRejection::Invalid);
     }
 
     if addr == 0 {
-        return false;
+        return Some(IpRejection::Invalid);
     }
 
     if ip_type == 4 {
-        if addr == 0 { return false; }
-        if addr >= u32::MAX as u128 { return false; }
-        if addr == 0x7f000001 { return false; } // Localhost
+        if addr >= u32::MAX as u128 { return Some(IpRejection::Invalid); }
+        for (network, prefix_len, rejection) in IPV4_SPECIAL_RANGES.iter() {
+            if is_ip_in_range(ip_type, addr, *network as u128, *prefix_len) {
+                return Some(*rejection);
+            }
+        }
     }
 
     if ip_type == 6 {
-        if addr == 0x0 { return false; }
-        if addr == u128::MAX { return false; }
-        if addr == 1 { return false; } // IPv6 localhost
+        if addr == u128::MAX { return Some(IpRejection::Invalid); }
+        for (network, prefix_len, rejection) in IPV6_SPECIAL_RANGES.iter() {
+            if is_ip_in_range(ip_type, addr, *network, *prefix_len) {
+                return Some(*rejection);
+            }
+        }
     }
 
-    return true;
+    return None;
+}
+
+#[cfg(test)]
+fn is_valid_ip_address(ip_type: u8, addr: u128) -> bool {
+    return get_ip_rejection(ip_type, addr).is_none();
 }
 
 #[cfg(test)]
 mod test {
-    use crate::subscribing::{is_valid_ip_type, is_valid_ip_address};
+    use crate::subscribing::{is_valid_ip_type, is_valid_ip_address, is_valid_ip_range, get_ip_rejection, IpRejection};
     use std::net::{Ipv6Addr, Ipv4Addr};
 
     // Generates an ipv6 address based on 8 ipv6 words and returns it as u128
@@ -215,4 +812,65 @@ mod test {
         assert_eq!(is_valid_ip_address(6, ipv6(0, 0, 0, 0, 0, 0, 0, 0)), false);
         assert_eq!(is_valid_ip_address(4, ipv6(0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff)), false);
     }
+
+    #[test]
+    fn test_ip_rejection_ipv4_special_ranges() {
+        assert_eq!(get_ip_rejection(4, ipv4(127, 0, 0, 1)), Some(IpRejection::Loopback));
+        assert_eq!(get_ip_rejection(4, ipv4(127, 255, 0, 3)), Some(IpRejection::Loopback));
+        assert_eq!(get_ip_rejection(4, ipv4(10, 1, 2, 3)), Some(IpRejection::Private));
+        assert_eq!(get_ip_rejection(4, ipv4(172, 16, 0, 1)), Some(IpRejection::Private));
+        assert_eq!(get_ip_rejection(4, ipv4(172, 31, 255, 254)), Some(IpRejection::Private));
+        assert_eq!(get_ip_rejection(4, ipv4(192, 168, 1, 1)), Some(IpRejection::Private));
+        assert_eq!(get_ip_rejection(4, ipv4(100, 64, 0, 1)), Some(IpRejection::Shared));
+        assert_eq!(get_ip_rejection(4, ipv4(100, 127, 255, 254)), Some(IpRejection::Shared));
+        assert_eq!(get_ip_rejection(4, ipv4(169, 254, 1, 1)), Some(IpRejection::LinkLocal));
+        assert_eq!(get_ip_rejection(4, ipv4(224, 0, 0, 1)), Some(IpRejection::Multicast));
+        assert_eq!(get_ip_rejection(4, ipv4(239, 255, 255, 250)), Some(IpRejection::Multicast));
+        assert_eq!(get_ip_rejection(4, ipv4(192, 0, 2, 1)), Some(IpRejection::Documentation));
+        assert_eq!(get_ip_rejection(4, ipv4(198, 51, 100, 1)), Some(IpRejection::Documentation));
+        assert_eq!(get_ip_rejection(4, ipv4(203, 0, 113, 1)), Some(IpRejection::Documentation));
+        assert_eq!(get_ip_rejection(4, ipv4(198, 19, 0, 1)), Some(IpRejection::Documentation));
+        assert_eq!(get_ip_rejection(4, ipv4(240, 0, 0, 1)), Some(IpRejection::Reserved));
+        assert_eq!(get_ip_rejection(4, ipv4(192, 0, 0, 8)), Some(IpRejection::Reserved));
+        assert_eq!(get_ip_rejection(4, ipv4(255, 255, 255, 255)), Some(IpRejection::Invalid));
+        assert_eq!(get_ip_rejection(4, ipv4(0, 0, 0, 0)), Some(IpRejection::Invalid));
+        assert_eq!(get_ip_rejection(4, ipv4(0, 0, 0, 1)), Some(IpRejection::ThisNetwork));
+        assert_eq!(get_ip_rejection(4, ipv4(0, 255, 255, 255)), Some(IpRejection::ThisNetwork));
+    }
+
+    #[test]
+    fn test_ip_rejection_ipv4_range_boundaries() {
+        assert_eq!(get_ip_rejection(4, ipv4(172, 15, 255, 255)), None);
+        assert_eq!(get_ip_rejection(4, ipv4(172, 32, 0, 0)), None);
+        assert_eq!(get_ip_rejection(4, ipv4(100, 63, 255, 255)), None);
+        assert_eq!(get_ip_rejection(4, ipv4(100, 128, 0, 0)), None);
+        assert_eq!(get_ip_rejection(4, ipv4(198, 20, 0, 0)), None);
+        assert_eq!(get_ip_rejection(4, ipv4(223, 255, 255, 255)), None);
+        assert_eq!(get_ip_rejection(4, ipv4(1, 1, 1, 1)), None);
+        assert_eq!(get_ip_rejection(4, ipv4(1, 0, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_ip_rejection_ipv6_special_ranges() {
+        assert_eq!(get_ip_rejection(6, ipv6(0, 0, 0, 0, 0, 0, 0, 1)), Some(IpRejection::Loopback));
+        assert_eq!(get_ip_rejection(6, ipv6(0, 0, 0, 0, 0, 0xffff, 0x0808, 0x0808)), Some(IpRejection::Ipv4Mapped));
+        assert_eq!(get_ip_rejection(6, ipv6(0xfc00, 0, 0, 0, 0, 0, 0, 1)), Some(IpRejection::UniqueLocal));
+        assert_eq!(get_ip_rejection(6, ipv6(0xfdff, 1, 2, 3, 4, 5, 6, 7)), Some(IpRejection::UniqueLocal));
+        assert_eq!(get_ip_rejection(6, ipv6(0xfe80, 0, 0, 0, 0, 0, 0, 1)), Some(IpRejection::LinkLocal));
+        assert_eq!(get_ip_rejection(6, ipv6(0xfebf, 0, 0, 0, 0, 0, 0, 1)), Some(IpRejection::LinkLocal));
+        assert_eq!(get_ip_rejection(6, ipv6(0xff02, 0, 0, 0, 0, 0, 0, 1)), Some(IpRejection::Multicast));
+        assert_eq!(get_ip_rejection(6, ipv6(0x2001, 0x0db8, 0, 0, 0, 0, 0, 1)), Some(IpRejection::Documentation));
+        assert_eq!(get_ip_rejection(6, ipv6(0x2001, 0x0db9, 0, 0, 0, 0, 0, 1)), None);
+        assert_eq!(get_ip_rejection(6, ipv6(0xfec0, 0, 0, 0, 0, 0, 0, 1)), None);
+    }
+
+    #[test]
+    fn test_is_valid_ip_range() {
+        assert_eq!(is_valid_ip_range(4, 0), true);
+        assert_eq!(is_valid_ip_range(4, 32), true);
+        assert_eq!(is_valid_ip_range(4, 33), false);
+        assert_eq!(is_valid_ip_range(6, 128), true);
+        assert_eq!(is_valid_ip_range(6, 129), false);
+        assert_eq!(is_valid_ip_range(5, 8), false);
+    }
 }
@@ -0,0 +1,71 @@
+use super::*;
+use sp_core::ed25519;
+
+impl<T: Trait> Module<T> {
+    /// Sets or removes the transport key of the neuron associated with the calling hotkey.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The calling hotkey is not in the active set.
+    /// - InvalidTransportKey : The key is all zeros.
+    /// - EndpointUpdateTooFast : The neuron updated its endpoints or key within the update cooldown.
+    /// - TooManyUpdatesThisBlock : The maximum number of updates this block is reached.
+    ///
+    pub fn do_set_transport_key(origin: T::Origin, key: Option<TransportKey>) -> dispatch::DispatchResult {
+        // --- We check the callers (hotkey) signature.
+        let hotkey_id = ensure_signed(origin)?;
+        ensure!(Self::is_hotkey_active(&hotkey_id), Error::<T>::NotActive);
+        ensure!(key.map_or(true, |key| is_valid_transport_key(&key)), Error::<T>::InvalidTransportKey);
+
+        let uid = Self::get_uid_for_hotkey(&hotkey_id);
+        ensure!(Self::update_cooldown_has_passed(uid), Error::<T>::EndpointUpdateTooFast);
+        ensure!(Self::check_and_increment_updates_per_block(), Error::<T>::TooManyUpdatesThisBlock);
+
+        let mut neuron = Self::get_neuron_for_uid(uid);
+        neuron.transport_key = key;
+        Neurons::<T>::insert(uid, &neuron);
+        Self::update_last_endpoint_update_for_neuron(uid);
+        Self::update_last_update_for_neuron(uid);
+
+        Self::deposit_event(RawEvent::TransportKeySet(uid));
+        Ok(())
+    }
+
+    /// Returns the transport key registered by the neuron, None when the uid does not
+    /// exist or the neuron has not registered a key.
+    ///
+    pub fn get_transport_key_for_neuron(uid: u64) -> Option<TransportKey> {
+        if !Neurons::<T>::contains_key(uid) {
+            return None;
+        }
+        Self::get_neuron_for_uid(uid).transport_key
+    }
+
+    /// Verifies that the challenge was signed by the ed25519 transport key of the neuron.
+    /// Returns false when the neuron has no ed25519 transport key. Noise peers authenticate
+    /// through the handshake instead, see fn transport_key_matches.
+    ///
+    pub fn verify_transport_signature(uid: u64, challenge: &[u8], signature: &[u8; 64]) -> bool {
+        match Self::get_transport_key_for_neuron(uid) {
+            Some(TransportKey::Ed25519(public)) => sp_io::crypto::ed25519_verify(
+                &ed25519::Signature::from_raw(*signature),
+                challenge,
+                &ed25519::Public::from_raw(public),
+            ),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the key presented by a peer, e.g. the remote static key of a
+    /// Noise handshake, is the transport key registered by the neuron.
+    ///
+    pub fn transport_key_matches(uid: u64, key: &TransportKey) -> bool {
+        Self::get_transport_key_for_neuron(uid).as_ref() == Some(key)
+    }
+}
+
+/// Returns false for an all zero key, which is never a valid public key.
+fn is_valid_transport_key(key: &TransportKey) -> bool {
+    match key {
+        TransportKey::Ed25519(bytes) | TransportKey::X25519(bytes) => bytes.iter().any(|b| *b != 0),
+    }
+}
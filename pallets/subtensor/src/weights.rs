@@ -1,35 +1,108 @@
 use super::*;
+use sp_arithmetic::{FixedU128, FixedPointNumber};
 
 
 impl<T: Trait> Module<T> {
-    pub fn do_set_weights(origin: T::Origin, uids: Vec<u64>, values: Vec<u32>) -> dispatch::DispatchResult
+    pub fn do_set_weights(origin: T::Origin, uids: Vec<u64>, values: Vec<u32>, version: Option<u64>) -> dispatch::DispatchResult
     {
-        // ---- We check the caller signature
-        let hotkey_id = ensure_signed(origin)?;
+        // ---- We check the caller signature, a weights key acts for the hotkey of its neuron.
+        let hotkey_id = Self::get_hotkey_for_weights_caller(&ensure_signed(origin)?);
 
+        // --- We check that the weights were computed against the current metagraph, if the
+        // caller passed the version it saw.
+        ensure!(Self::is_current_metagraph_version(version), Error::<T>::StaleMetagraph);
+        Self::set_weights_for_hotkey(hotkey_id, uids, values)
+    }
+
+    pub fn do_update_weights(origin: T::Origin, changes: Vec<(u64, u32)>, version: Option<u64>) -> dispatch::DispatchResult
+    {
+        // ---- We check the caller signature, a weights key acts for the hotkey of its neuron.
+        let hotkey_id = Self::get_hotkey_for_weights_caller(&ensure_signed(origin)?);
+        ensure!(Self::is_hotkey_active(&hotkey_id), Error::<T>::NotActive);
+
+        // --- We check that the changes were computed against the current metagraph, if the
+        // caller passed the version it saw.
+        ensure!(Self::is_current_metagraph_version(version), Error::<T>::StaleMetagraph);
+
+        // --- We check that the number of changes is bounded by MaxWeightsPerNeuron.
+        ensure!(changes.len() as u32 <= T::MaxWeightsPerNeuron::get(), Error::<T>::TooManyWeights);
+
+        // --- We check that every uid is changed once only.
+        let changed_uids: Vec<u64> = changes.iter().map(|(uid, _)| *uid).collect();
+        ensure!(!has_duplicate_uids(&changed_uids), Error::<T>::DuplicateUids);
+
+        // --- We apply the changes to the sorted edges, a zero value removes the edge.
+        // Stale edges are dropped in memory first, so they are not carried over. The stored
+        // row is only written, and pruned, once the resulting weights pass every check.
+        let neuron = Self::get_neuron_for_hotkey(&hotkey_id);
+        let mut weights = match Self::get_pruned_weights_for_neuron(&neuron) {
+            Some((uids, values)) => uids.into_iter().zip(values.into_iter()).collect(),
+            None => Weights::get(neuron.uid),
+        };
+        for (uid, value) in changes {
+            match find_edge(&weights, uid) {
+                Ok(position) if value == 0 => { weights.remove(position); }
+                Ok(position) => { weights[position].1 = value; }
+                Err(position) if value != 0 => { weights.insert(position, (uid, value)); }
+                Err(_) => {}
+            }
+        }
+
+        // --- The resulting weights are checked, normalized and set as in set_weights.
+        let (uids, values) = weights.into_iter().unzip();
+        Self::set_weights_for_hotkey(hotkey_id, uids, values)
+    }
+
+    /**
+    * Returns true if no version was passed, or if it matches the current MetagraphVersion.
+    */
+    pub fn is_current_metagraph_version(version: Option<u64>) -> bool {
+        version.map_or(true, |version| version == MetagraphVersion::get())
+    }
+
+    /**
+    * Checks and sets the weights of the neuron associated with the hotkey. Shared by
+    * set_weights and reveal_weights once the caller signature is checked.
+    */
+    pub fn set_weights_for_hotkey(hotkey_id: T::AccountId, uids: Vec<u64>, values: Vec<u32>) -> dispatch::DispatchResult
+    {
         // ---- We check to see that the calling neuron is in the active set.
         ensure!(Self::is_hotkey_active(&hotkey_id), Error::<T>::NotActive);
         let neuron = Self::get_neuron_for_hotkey(&hotkey_id);
 
+        // --- We check that the neuron did not set its weights within the rate limit.
+        ensure!(Self::weights_rate_limit_has_passed(neuron.uid), Error::<T>::SettingWeightsTooFast);
+
         // --- We check that the length of these two lists are equal.
         ensure!(uids_match_values(&uids, &values), Error::<T>::WeightVecNotEqualSize);
 
+        // --- We check that the number of weights is bounded by MaxWeightsPerNeuron.
+        ensure!(uids.len() as u32 <= T::MaxWeightsPerNeuron::get(), Error::<T>::TooManyWeights);
+
         // --- We check if the uids vector does not contain duplicate ids
         ensure!(!has_duplicate_uids(&uids), Error::<T>::DuplicateUids);
 
         // --- We check if the weight uids are valid
         ensure!(!Self::contains_invalid_uids(&uids), Error::<T>::InvalidUid);
 
+        // --- We check that non empty weights do not sum to zero, they can not be normalized.
+        ensure!(values.is_empty() || !is_all_zero(&values), Error::<T>::WeightsSumToZero);
+
+        // --- We check the normalized weights against the root controlled limits.
+        let normalized_values = normalize(values);
+        ensure!(self_weight(neuron.uid, &uids, &normalized_values) <= MaxSelfWeight::get(), Error::<T>::SelfWeightTooHigh);
+        ensure!(count_non_self_weights(neuron.uid, &uids, &normalized_values) >= MinNonSelfWeights::get(), Error::<T>::NotEnoughNonSelfWeights);
+        ensure!(max_weight(&normalized_values) <= MaxEdgeWeight::get(), Error::<T>::EdgeWeightTooHigh);
 
         // ---- We call an inflation emit before setting the weights
         // to ensure that the caller is pays for his previously set weights.
         // TODO(const): can we pay for this transaction through inflation.
         Self::emit_for_neuron(&neuron);
 
-        let normalized_values = normalize(values);
-
         // --- We update the weights under the uid map.
         Self::set_new_weights(&neuron, &uids, &normalized_values);
+        Self::update_last_update_for_neuron(neuron.uid);
+        Self::update_last_weights_set_for_neuron(neuron.uid);
 
         // ---- Emit the staking event.
         Self::deposit_event(RawEvent::WeightsSet(hotkey_id));
@@ -55,23 +128,224 @@ impl<T: Trait> Module<T> {
 
     /**
     * Sets the actual weights. This function takes two parameters: uids, values
-    * that contain the weight for each uid.
+    * that contain the weight for each uid. The edges are stored sorted by uid.
     * This function assumes both vectors are of the same size, and is agnostic if the specifed
     * uid's exist or not.
     */
     pub fn set_new_weights(neuron: &NeuronMetadataOf<T>, uids: &Vec<u64>, values: &Vec<u32>) {
-        WeightVals::insert(neuron.uid, &values);
-        WeightUids::insert(neuron.uid, &uids);
+        let mut weights: Vec<(u64, u32)> = uids.iter().cloned().zip(values.iter().cloned()).collect();
+        weights.sort_by_key(|(uid, _)| *uid);
+        Weights::insert(neuron.uid, &weights);
+        WeightsVersion::insert(neuron.uid, MetagraphVersion::get());
     }
 
 
     pub fn remove_weight_matrix_for_neuron(neuron: &NeuronMetadataOf<T>) {
-        WeightVals::remove(neuron.uid);
-        WeightUids::remove(neuron.uid);
+        Weights::remove(neuron.uid);
+        WeightsVersion::remove(neuron.uid);
+    }
+
+    pub fn get_weights_set_rate_limit() -> T::BlockNumber {
+        return WeightsSetRateLimitOverride::<T>::get().unwrap_or_else(T::WeightsSetRateLimit::get);
+    }
+
+    pub fn update_last_weights_set_for_neuron(uid: u64) {
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        LastWeightsSet::<T>::insert(uid, current_block);
+    }
+
+    /**
+    * Returns true if the neuron never set its weights or the weights rate limit has passed
+    * since it last did.
+    */
+    pub fn weights_rate_limit_has_passed(uid: u64) -> bool {
+        let current_block: T::BlockNumber = system::Module::<T>::block_number();
+        match LastWeightsSet::<T>::get(uid) {
+            Some(last_set) => last_set + Self::get_weights_set_rate_limit() <= current_block,
+            None => true,
+        }
     }
 
+    /**
+    * Bumps the metagraph version when a neuron is added to or removed from the metagraph.
+    * A removal schedules a full sweep of the weights for edges pointing at the vacated uid.
+    */
+    pub fn bump_metagraph_version_on_add(uid: u64) {
+        let version = MetagraphVersion::get() + 1;
+        MetagraphVersion::put(version);
+        UidVersion::insert(uid, version);
+    }
+
+    pub fn bump_metagraph_version_on_remove(uid: u64) {
+        MetagraphVersion::mutate(|version| *version += 1);
+        UidVersion::remove(uid);
+        PendingWeightPrunes::put(Self::get_neuron_count());
+    }
+
+    /**
+    * Returns true if the edge from the source uid points at a vacant uid, or at a uid
+    * taken over by a neuron added after the source last wrote its weights.
+    */
+    pub fn is_stale_edge(source_uid: u64, dest_uid: u64) -> bool {
+        !Self::is_uid_active(dest_uid) || UidVersion::get(dest_uid) > WeightsVersion::get(source_uid)
+    }
+
+    /**
+    * Removes the stale edges from the weights of the neuron and re-normalizes the
+    * remaining weights. Returns true if edges were removed.
+    *
+    * Pruning is exempt from the MaxSelfWeight, MinNonSelfWeights and MaxEdgeWeight
+    * limits: the re-normalized weights can exceed them, since the edges can not be kept.
+    * The limits apply again the next time the neuron sets its weights.
+    */
+    pub fn prune_stale_weights_for_neuron(neuron: &NeuronMetadataOf<T>) -> bool {
+        match Self::get_pruned_weights_for_neuron(neuron) {
+            Some((uids, values)) => {
+                Self::set_new_weights(neuron, &uids, &values);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /**
+    * Returns the weights of the neuron without its stale edges, re-normalized, or None if
+    * the neuron has no stale edges. Nothing is written.
+    */
+    pub fn get_pruned_weights_for_neuron(neuron: &NeuronMetadataOf<T>) -> Option<(Vec<u64>, Vec<u32>)> {
+        let weights = Weights::get(neuron.uid);
+        let (uids, values): (Vec<u64>, Vec<u32>) = weights.iter()
+            .filter(|(uid, _)| !Self::is_stale_edge(neuron.uid, *uid))
+            .cloned()
+            .unzip();
+
+        if uids.len() == weights.len() {
+            return None;
+        }
+        Some((uids, normalize(values)))
+    }
+
+    /**
+    * Returns the weight of pruning the stale edges from a row with the given number of
+    * edges: the row is read, each edge reads the activity and version of its destination
+    * and the version of the source, and the row and its version are written back.
+    */
+    pub fn prune_stale_weights_weight(edges: u64) -> Weight {
+        T::DbWeight::get().reads_writes(1 + 3 * edges, 2)
+    }
+
+    /**
+    * Returns the weight of pruning a row of MaxWeightsPerNeuron edges, the most any row
+    * can hold. Calls which emit for a neuron prune its row first and are weighed with it.
+    */
+    pub fn max_prune_stale_weights_weight() -> Weight {
+        Self::prune_stale_weights_weight(T::MaxWeightsPerNeuron::get() as u64)
+    }
+
+    /**
+    * Prunes the stale edges left by removed or recycled uids from the weights of all neurons.
+    *
+    * The sweep is bounded: every block at most MaxWeightPrunesPerBlock rows are checked,
+    * starting at the uid where the previous sweep stopped, until every row has been checked
+    * once since the last removal. The uids of the pruned rows are listed in a
+    * StaleWeightsPruned event.
+    *
+    * # Returns
+    * 	* weight (Weight):
+    * 		- The database weight of the rows checked and of the edges visited in them.
+    */
+    pub fn sweep_stale_weights() -> Weight {
+        let pending = PendingWeightPrunes::get();
+        let neuron_count = Self::get_neuron_count();
+        if pending == 0 || neuron_count == 0 {
+            return 0;
+        }
+
+        let checks = sp_std::cmp::min(T::MaxWeightPrunesPerBlock::get(), sp_std::cmp::min(pending, neuron_count));
+        let mut pruned: Vec<u64> = vec![];
+
+        // --- The pending count, neuron count and cursor are read, the cursor and pending count written.
+        let mut weight: Weight = T::DbWeight::get().reads_writes(3, 2);
+        let mut uid = WeightsPruneCursor::get() % neuron_count;
+        for _ in 0..checks {
+            weight = weight.saturating_add(T::DbWeight::get().reads(1));
+            if Self::is_uid_active(uid) {
+                let neuron = Self::get_neuron_for_uid(uid);
+                let edges = Weights::decode_len(uid).unwrap_or(0) as u64;
+                weight = weight.saturating_add(T::DbWeight::get().reads(2)).saturating_add(Self::prune_stale_weights_weight(edges));
+                if Self::prune_stale_weights_for_neuron(&neuron) {
+                    pruned.push(uid);
+                }
+            }
+            uid = (uid + 1) % neuron_count;
+        }
+        WeightsPruneCursor::put(uid);
+        PendingWeightPrunes::put(pending - checks);
+
+        if !pruned.is_empty() {
+            Self::deposit_event(RawEvent::StaleWeightsPruned(pruned));
+        }
+        weight
+    }
+
+    /**
+    * Returns the weights of the neuron as a vector of uids and a vector of values,
+    * both sorted by uid.
+    */
     pub fn get_weights_for_neuron(neuron: &NeuronMetadataOf<T>) -> (Vec<u64>, Vec<u32>) {
-        (WeightUids::get(neuron.uid), WeightVals::get(neuron.uid))
+        Weights::get(neuron.uid).into_iter().unzip()
+    }
+
+    /**
+    * Returns the weight on the edge from the source uid to the destination uid,
+    * zero when there is no such edge.
+    */
+    pub fn get_weight_for_edge(source_uid: u64, dest_uid: u64) -> u32 {
+        let weights = Weights::get(source_uid);
+        match find_edge(&weights, dest_uid) {
+            Ok(position) => weights[position].1,
+            Err(_) => 0,
+        }
+    }
+
+    /**
+    * Returns the weights of the neuron with the uid as they count towards emission: stale
+    * edges the sweep has not reached yet are left out and the remaining weights re-normalized.
+    * Each weight is returned both as the raw u32 value and as a fraction of u32::MAX.
+    */
+    pub fn get_weight_infos(uid: u64) -> Vec<WeightInfo> {
+        if !Self::is_uid_active(uid) {
+            return vec![];
+        }
+
+        let weights = Weights::get(uid);
+        let (uids, mut values): (Vec<u64>, Vec<u32>) = weights.iter()
+            .filter(|(dest_uid, _)| !Self::is_stale_edge(uid, *dest_uid))
+            .cloned()
+            .unzip();
+        if uids.len() != weights.len() {
+            values = normalize(values);
+        }
+
+        uids.into_iter().zip(values).map(|(dest_uid, weight)| WeightInfo {
+            uid: dest_uid,
+            weight,
+            fraction: FixedU128::saturating_from_rational(weight, u32::max_value()),
+        }).collect()
+    }
+
+    /**
+    * Returns one page of the weight matrix: the weights of the active neurons with uids
+    * in start_uid..start_uid + count. The count is clamped to MAX_WEIGHT_MATRIX_PAGE, and
+    * vacant uids are skipped, so a page can hold fewer than count rows.
+    */
+    pub fn get_weight_matrix(start_uid: u64, count: u64) -> Vec<NeuronWeights> {
+        let count = sp_std::cmp::min(count, MAX_WEIGHT_MATRIX_PAGE);
+        let end_uid = sp_std::cmp::min(start_uid.saturating_add(count), Self::get_neuron_count());
+        (start_uid..end_uid)
+            .filter(|uid| Self::is_uid_active(*uid))
+            .map(|uid| NeuronWeights { uid, weights: Self::get_weight_infos(uid) })
+            .collect()
     }
 
     pub fn contains_invalid_uids(uids: &Vec<u64>) -> bool {
@@ -85,6 +359,35 @@ impl<T: Trait> Module<T> {
     }
 }
 
+/**
+* Binary searches the sorted edges for the destination uid. Returns the position of
+* the edge, or the position where it would be inserted.
+*/
+fn find_edge(weights: &Vec<(u64, u32)>, dest_uid: u64) -> Result<usize, usize> {
+    weights.binary_search_by_key(&dest_uid, |(uid, _)| *uid)
+}
+
+/**
+* Returns the weight the neuron sets on itself, zero when it has no self edge.
+*/
+fn self_weight(uid: u64, uids: &Vec<u64>, values: &Vec<u32>) -> u32 {
+    match uids.iter().position(|x| *x == uid) {
+        Some(position) => values[position],
+        None => 0,
+    }
+}
+
+/**
+* Returns the number of edges to other neurons which carry a non zero weight.
+*/
+fn count_non_self_weights(uid: u64, uids: &Vec<u64>, values: &Vec<u32>) -> u32 {
+    uids.iter().zip(values.iter()).filter(|(x, value)| **x != uid && **value != 0).count() as u32
+}
+
+fn max_weight(values: &Vec<u32>) -> u32 {
+    values.iter().cloned().max().unwrap_or(0)
+}
+
 fn uids_match_values(uids: &Vec<u64>, values: &Vec<u32>) -> bool {
     return uids.len() == values.len();
 }
@@ -94,16 +397,17 @@ fn uids_match_values(uids: &Vec<u64>, values: &Vec<u32>) -> bool {
 * If it does, an attacker could
 */
 fn has_duplicate_uids(items: &Vec<u64>) -> bool {
-    let mut parsed: Vec<u64> = Vec::new();
-    for item in items {
-        if parsed.contains(&item) { return true; }
-        parsed.push(item.clone());
-    }
-
-    return false;
+    let mut sorted = items.clone();
+    sorted.sort_unstable();
+    return sorted.windows(2).any(|pair| pair[0] == pair[1]);
 }
 
 
+/**
+* Scales the weights so they sum to exactly u32::max. The units lost to integer division
+* are handed out one by one to the weights with the largest remainders (largest remainder
+* method), ties going to the first weight. All zero weights are returned unchanged.
+*/
 fn normalize(mut weights: Vec<u32>) -> Vec<u32> {
     let sum: u64 = weights.iter().map(|x| *x as u64).sum();
 
@@ -111,28 +415,46 @@ fn normalize(mut weights: Vec<u32>) -> Vec<u32> {
         return weights;
     }
 
-    weights.iter_mut().for_each(|x| {
-        *x = (*x as u64 * u32::max_value() as u64 / sum) as u32;
-    });
+    let target = u32::max_value() as u64;
+    let mut assigned: u64 = 0;
+    let mut remainders: Vec<(u64, usize)> = Vec::with_capacity(weights.len());
+    for (i, x) in weights.iter_mut().enumerate() {
+        let scaled = *x as u64 * target;
+        *x = (scaled / sum) as u32;
+        assigned += scaled / sum;
+        remainders.push((scaled % sum, i));
+    }
+
+    // --- The remainders sum to (target - assigned) * sum and each is smaller than sum,
+    // so the weights receiving a unit all have a non zero remainder and can not overflow.
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for (_, i) in remainders.iter().take((target - assigned) as usize) {
+        weights[*i] += 1;
+    }
 
     return weights;
 }
 
+fn is_all_zero(values: &Vec<u32>) -> bool {
+    values.iter().all(|x| *x == 0)
+}
+
 
 #[cfg(test)]
 mod tests {
-    use crate::weights::{normalize, has_duplicate_uids};
+    use crate::weights::{normalize, is_all_zero, has_duplicate_uids, find_edge, self_weight, count_non_self_weights, max_weight};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
 
     #[test]
     fn normalize_sum_smaller_than_one() {
         let values: Vec<u32> = vec![u32::max_value() / 10, u32::max_value() / 10];
-        assert_eq!(normalize(values), vec![u32::max_value() / 2, u32::max_value() / 2]);
+        assert_eq!(normalize(values), vec![u32::max_value() / 2 + 1, u32::max_value() / 2]);
     }
 
     #[test]
     fn normalize_sum_greater_than_one() {
         let values: Vec<u32> = vec![u32::max_value() / 7, u32::max_value() / 7];
-        assert_eq!(normalize(values), vec![u32::max_value() / 2, u32::max_value() / 2]);
+        assert_eq!(normalize(values), vec![u32::max_value() / 2 + 1, u32::max_value() / 2]);
     }
 
     #[test]
@@ -144,7 +466,75 @@ mod tests {
     #[test]
     fn normalize_values_maxed() {
         let weights: Vec<u32> = vec![u32::max_value(), u32::max_value()];
-        assert_eq!(normalize(weights), vec![u32::max_value() / 2, u32::max_value() / 2]);
+        assert_eq!(normalize(weights), vec![u32::max_value() / 2 + 1, u32::max_value() / 2]);
+    }
+
+    #[test]
+    fn normalize_remainder_goes_to_largest_remainders() {
+        // 2/4, 1/4 and 1/4 of u32::max leave remainders of 2/4, 3/4 and 3/4 of a unit.
+        let weights: Vec<u32> = vec![2, 1, 1];
+        assert_eq!(normalize(weights), vec![2147483647, 1073741824, 1073741824]);
+    }
+
+    #[test]
+    fn normalize_all_zero_unchanged() {
+        for len in 1..10 {
+            let weights: Vec<u32> = vec![0; len];
+            assert!(is_all_zero(&weights));
+            assert_eq!(normalize(weights.clone()), weights);
+        }
+    }
+
+    #[test]
+    fn normalize_sums_to_max_for_random_weights() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..2000 {
+            let len = rng.gen_range(1, 64);
+            let weights: Vec<u32> = (0..len).map(|_| match rng.gen_range(0, 4) {
+                0 => 0,
+                1 => rng.gen_range(0, 10),
+                2 => u32::max_value() - rng.gen_range(0, 10),
+                _ => rng.gen(),
+            }).collect();
+            let sum: u128 = weights.iter().map(|x| *x as u128).sum();
+
+            let normalized = normalize(weights.clone());
+            assert_eq!(normalized.len(), weights.len());
+            if sum == 0 {
+                assert_eq!(normalized, weights);
+                continue;
+            }
+            assert_eq!(normalized.iter().map(|x| *x as u64).sum::<u64>(), u32::max_value() as u64);
+
+            // --- Every weight is its exact share rounded down or up, zero weights stay zero.
+            for (weight, value) in weights.iter().zip(normalized.iter()) {
+                let floor = *weight as u128 * u32::max_value() as u128 / sum;
+                assert!(*value as u128 == floor || *value as u128 == floor + 1);
+                assert!(*weight != 0 || *value == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn find_edge_sorted() {
+        let weights = vec![(1, 10), (4, 40), (9, 90)];
+        assert_eq!(find_edge(&weights, 4), Ok(1));
+        assert_eq!(find_edge(&weights, 9), Ok(2));
+        assert_eq!(find_edge(&weights, 5), Err(2));
+        assert_eq!(find_edge(&vec![], 5), Err(0));
+    }
+
+    #[test]
+    fn self_weight_and_non_self_weights() {
+        let uids = vec![3, 1, 2];
+        let values = vec![30, 10, 20];
+        assert_eq!(self_weight(1, &uids, &values), 10);
+        assert_eq!(self_weight(4, &uids, &values), 0);
+        assert_eq!(count_non_self_weights(1, &uids, &values), 2);
+        assert_eq!(count_non_self_weights(4, &uids, &values), 3);
+        assert_eq!(count_non_self_weights(1, &uids, &vec![0, 10, 20]), 1);
+        assert_eq!(max_weight(&values), 30);
+        assert_eq!(max_weight(&vec![]), 0);
     }
 
     #[test]
@@ -158,4 +548,11 @@ mod tests {
         let weights = vec![1, 2, 3, 4, 5];
         assert_eq!(has_duplicate_uids(&weights), false);
     }
+
+    #[test]
+    fn has_duplicate_elements_unsorted() {
+        assert_eq!(has_duplicate_uids(&vec![5, 1, 3, 1]), true);
+        assert_eq!(has_duplicate_uids(&vec![5, 1, 3, 2]), false);
+        assert_eq!(has_duplicate_uids(&vec![]), false);
+    }
 }
@@ -0,0 +1,96 @@
+use super::*;
+
+impl<T: Trait> Module<T> {
+    /// Authorizes the weights key to set the weights of the neuron on behalf of its hotkey.
+    /// The previous weights key of the neuron, if any, is revoked. Setting the current
+    /// weights key of the neuron again is a no-op.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The hotkey is not in the active set.
+    /// - NonAssociatedColdKey : The caller is not the coldkey of the neuron.
+    /// - ColdkeyNotConfirmed : The coldkey has not accepted the hotkey.
+    /// - WeightsKeyIsHotkey : The weights key is the hotkey of an active neuron.
+    /// - WeightsKeyInUse : The weights key is already authorized for a neuron.
+    ///
+    pub fn do_set_weights_key(origin: T::Origin, hotkey: T::AccountId, weights_key: T::AccountId) -> dispatch::DispatchResult {
+        // --- We check the callers (coldkey) signature.
+        let coldkey = ensure_signed(origin)?;
+        let neuron = Self::get_neuron_for_coldkey_call(&coldkey, &hotkey)?;
+        if WeightsKeys::<T>::get(neuron.uid).as_ref() == Some(&weights_key) {
+            return Ok(());
+        }
+
+        // --- A weights key must resolve to a single neuron, so it can be neither a hotkey
+        // nor the weights key of another neuron.
+        ensure!(!Self::is_hotkey_active(&weights_key), Error::<T>::WeightsKeyIsHotkey);
+        ensure!(!WeightsKeyUid::<T>::contains_key(&weights_key), Error::<T>::WeightsKeyInUse);
+
+        Self::remove_weights_key_for_neuron(neuron.uid);
+        WeightsKeys::<T>::insert(neuron.uid, &weights_key);
+        WeightsKeyUid::<T>::insert(&weights_key, neuron.uid);
+
+        Self::deposit_event(RawEvent::WeightsKeySet(hotkey, weights_key));
+        Ok(())
+    }
+
+    /// Revokes the weights key of the neuron.
+    ///
+    /// It throws the following errors if there is something wrong
+    /// - NotActive : The hotkey is not in the active set.
+    /// - NonAssociatedColdKey : The caller is not the coldkey of the neuron.
+    /// - ColdkeyNotConfirmed : The coldkey has not accepted the hotkey.
+    /// - NoWeightsKey : The neuron has no weights key.
+    ///
+    pub fn do_revoke_weights_key(origin: T::Origin, hotkey: T::AccountId) -> dispatch::DispatchResult {
+        // --- We check the callers (coldkey) signature.
+        let coldkey = ensure_signed(origin)?;
+        let neuron = Self::get_neuron_for_coldkey_call(&coldkey, &hotkey)?;
+        ensure!(WeightsKeys::<T>::contains_key(neuron.uid), Error::<T>::NoWeightsKey);
+
+        Self::remove_weights_key_for_neuron(neuron.uid);
+
+        Self::deposit_event(RawEvent::WeightsKeyRevoked(hotkey));
+        Ok(())
+    }
+
+    fn get_neuron_for_coldkey_call(coldkey: &T::AccountId, hotkey: &T::AccountId) -> Result<NeuronMetadataOf<T>, dispatch::DispatchError> {
+        ensure!(Self::is_hotkey_active(hotkey), Error::<T>::NotActive);
+        let neuron = Self::get_neuron_for_hotkey(hotkey);
+        ensure!(Self::neuron_belongs_to_coldkey(&neuron, coldkey), Error::<T>::NonAssociatedColdKey);
+        ensure!(neuron.coldkey_confirmed, Error::<T>::ColdkeyNotConfirmed);
+        Ok(neuron)
+    }
+
+    /// Removes the weights key of the neuron, if any.
+    ///
+    pub fn remove_weights_key_for_neuron(uid: u64) {
+        if let Some(weights_key) = WeightsKeys::<T>::take(uid) {
+            WeightsKeyUid::<T>::remove(&weights_key);
+        }
+    }
+
+    /// Revokes the weights key which equals the account, if any. Called when the account
+    /// becomes the hotkey of a neuron, as a weights key can not be a hotkey.
+    ///
+    pub fn revoke_weights_key_of_account(account: &T::AccountId) {
+        if let Some(uid) = WeightsKeyUid::<T>::get(account) {
+            Self::remove_weights_key_for_neuron(uid);
+            if Self::is_uid_active(uid) {
+                Self::deposit_event(RawEvent::WeightsKeyRevoked(Self::get_neuron_for_uid(uid).hotkey));
+            }
+        }
+    }
+
+    /// Returns the hotkey a set_weights or update_weights caller acts for: the hotkey of
+    /// the neuron if the caller is its weights key, the caller itself otherwise.
+    ///
+    pub fn get_hotkey_for_weights_caller(caller: &T::AccountId) -> T::AccountId {
+        if Self::is_hotkey_active(caller) {
+            return caller.clone();
+        }
+        match WeightsKeyUid::<T>::get(caller) {
+            Some(uid) if Self::is_uid_active(uid) => Self::get_neuron_for_uid(uid).hotkey,
+            _ => caller.clone(),
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use pallet_subtensor::{Error};
+use frame_support::{assert_ok};
+use frame_system::Trait;
+mod mock;
+use mock::*;
+use pallet_subtensor::{Call as SubtensorCall};
+use frame_support::dispatch::{GetDispatchInfo, DispatchInfo};
+use frame_support::weights::{DispatchClass, Pays};
+use sp_core::H256;
+
+fn commit_hash(hotkey: u64, uids: &Vec<u64>, values: &Vec<u32>, salt: &Vec<u8>) -> H256 {
+	SubtensorModule::get_weights_commit_hash(&hotkey, uids, values, salt)
+}
+
+/********************************************
+	commit_reveal::do_commit_weights() tests
+*********************************************/
+#[test]
+fn fee_and_weight_of_commit_weights_is_zero() {
+	new_test_ext().execute_with(|| {
+		let call = SubtensorCall::commit_weights(H256::zero());
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: 0,
+			class: DispatchClass::Normal,
+			pays_fee: Pays::No
+		});
+	});
+}
+
+#[test]
+fn test_commit_weights_ok() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(1, 667);
+		let hash = commit_hash(1, &vec![neuron.uid], &vec![u32::MAX], &b"salt".to_vec());
+
+		run_to_block(2);
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), hash));
+		assert_eq!(SubtensorModule::weight_commits(neuron.uid), Some((hash, 2)));
+
+		// --- The weights do not change until they are revealed.
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron), (vec![neuron.uid], vec![u32::MAX]));
+	});
+}
+
+#[test]
+fn test_commit_weights_err_not_active() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), H256::zero());
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+#[test]
+fn test_commit_weights_respects_weights_rate_limit() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(1, 667);
+		let uids = vec![neuron.uid];
+		let values = vec![u32::MAX];
+		let salt = b"salt".to_vec();
+		assert_ok!(SubtensorModule::set_weights_rate_limit(<<Test as Trait>::Origin>::root(), Some(5)));
+
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), commit_hash(1, &uids, &values, &salt)));
+
+		// --- The pending commit can not be replaced within the rate limit.
+		let result = SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), H256::zero());
+		assert_eq!(result, Err(Error::<Test>::SettingWeightsTooFast.into()));
+
+		// --- Revealing sets the weights, which restarts the rate limit.
+		run_to_block(2);
+		assert_ok!(SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(1), uids, values, salt, None));
+		run_to_block(5);
+		let result = SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), H256::zero());
+		assert_eq!(result, Err(Error::<Test>::SettingWeightsTooFast.into()));
+
+		run_to_block(7);
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), H256::zero()));
+	});
+}
+
+/********************************************
+	commit_reveal::do_reveal_weights() tests
+*********************************************/
+#[test]
+fn test_reveal_weights_ok() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(1, 667);
+		let neuron_b = subscribe_ok_neuron(2, 667);
+		let uids = vec![neuron_b.uid];
+		let values = vec![u32::MAX];
+		let salt = b"salt".to_vec();
+
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), commit_hash(1, &uids, &values, &salt)));
+		run_to_block(WeightsRevealWindow::get());
+		assert_ok!(SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(1), uids.clone(), values.clone(), salt, None));
+
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (uids, values));
+		assert_eq!(SubtensorModule::weight_commits(neuron_a.uid), None);
+	});
+}
+
+#[test]
+fn test_reveal_weights_err_no_commit() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(1, 667);
+		let result = SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(1), vec![neuron.uid], vec![u32::MAX], b"salt".to_vec(), None);
+		assert_eq!(result, Err(Error::<Test>::NoWeightsCommit.into()));
+	});
+}
+
+#[test]
+fn test_reveal_weights_err_expired() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(1, 667);
+		let uids = vec![neuron.uid];
+		let values = vec![u32::MAX];
+		let salt = b"salt".to_vec();
+
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), commit_hash(1, &uids, &values, &salt)));
+		run_to_block(WeightsRevealWindow::get() + 1);
+
+		let result = SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(1), uids.clone(), values.clone(), salt.clone(), None);
+		assert_eq!(result, Err(Error::<Test>::WeightsCommitExpired.into()));
+
+		// --- The expired commit is removed.
+		assert_eq!(SubtensorModule::weight_commits(neuron.uid), None);
+		let result = SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(1), uids, values, salt, None);
+		assert_eq!(result, Err(Error::<Test>::NoWeightsCommit.into()));
+	});
+}
+
+#[test]
+fn test_swap_hotkey_clears_weights_commit() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		let uids = vec![neuron.uid];
+		let values = vec![u32::MAX];
+		let salt = b"salt".to_vec();
+
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), commit_hash(1, &uids, &values, &salt)));
+		assert_ok!(SubtensorModule::swap_hotkey(<<Test as Trait>::Origin>::signed(667), 1, 2));
+		assert_eq!(SubtensorModule::weight_commits(neuron.uid), None);
+
+		// --- The new hotkey can commit again.
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(2), commit_hash(2, &uids, &values, &salt)));
+	});
+}
+
+#[test]
+fn test_reveal_weights_err_mismatch() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(1, 667);
+		let neuron_b = subscribe_ok_neuron(2, 667);
+		let salt = b"salt".to_vec();
+
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), commit_hash(1, &vec![neuron_b.uid], &vec![u32::MAX], &salt)));
+
+		// --- Other weights, another salt or a commit copied from another hotkey do not match.
+		let result = SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(1), vec![neuron_a.uid], vec![u32::MAX], salt.clone(), None);
+		assert_eq!(result, Err(Error::<Test>::InvalidWeightsReveal.into()));
+
+		let result = SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(1), vec![neuron_b.uid], vec![u32::MAX], b"pepper".to_vec(), None);
+		assert_eq!(result, Err(Error::<Test>::InvalidWeightsReveal.into()));
+
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(2), SubtensorModule::weight_commits(neuron_a.uid).unwrap().0));
+		let result = SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(2), vec![neuron_b.uid], vec![u32::MAX], salt, None);
+		assert_eq!(result, Err(Error::<Test>::InvalidWeightsReveal.into()));
+	});
+}
+
+#[test]
+fn test_reveal_weights_err_stale_metagraph() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(1, 667);
+		let version = SubtensorModule::metagraph_version();
+		let neuron_b = subscribe_ok_neuron(2, 667);
+		let uids = vec![neuron_b.uid];
+		let values = vec![u32::MAX];
+		let salt = b"salt".to_vec();
+
+		// --- The version is not part of the commit, a stale reveal leaves the commit pending.
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), commit_hash(1, &uids, &values, &salt)));
+		let result = SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(1), uids.clone(), values.clone(), salt.clone(), Some(version));
+		assert_eq!(result, Err(Error::<Test>::StaleMetagraph.into()));
+		assert!(SubtensorModule::weight_commits(neuron_a.uid).is_some());
+
+		let version = SubtensorModule::metagraph_version();
+		assert_ok!(SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(1), uids.clone(), values.clone(), salt, Some(version)));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (uids, values));
+	});
+}
+
+#[test]
+fn test_reveal_weights_applies_set_weights_checks() {
+	new_test_ext().execute_with(|| {
+		let _neuron = subscribe_ok_neuron(1, 667);
+		let uids = vec![9999];
+		let values = vec![u32::MAX];
+		let salt = b"salt".to_vec();
+
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), commit_hash(1, &uids, &values, &salt)));
+		let result = SubtensorModule::reveal_weights(<<Test as Trait>::Origin>::signed(1), uids, values, salt, None);
+		assert_eq!(result, Err(Error::<Test>::InvalidUid.into()));
+	});
+}
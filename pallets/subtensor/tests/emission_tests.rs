@@ -6,6 +6,7 @@ use mock::*;
 
 use frame_system as system;
 use substrate_fixed::types::U64F64;
+use rand::{Rng, SeedableRng, rngs::StdRng};
 
 fn random_neuron_with_stake(hotkey:u64, stake_to_init: u64, ip:u128, port:u16, ip_type:u8, modality: u8, coldkey:u64) -> NeuronMetadata<u64> {
     let _ = SubtensorModule::subscribe(<<Test as system::Trait>::Origin>::signed(hotkey), ip, port, ip_type, modality, coldkey);
@@ -23,6 +24,7 @@ fn test_emit_does_not_exist() {
 	new_test_ext().execute_with(|| {
         // Let's subscribe a new neuron to the chain.
         let neuron = NeuronMetadata::<u64> {
+            version: 2,
             ip: 0,
             port: 0,
             ip_type: 0,
@@ -30,6 +32,8 @@ fn test_emit_does_not_exist() {
             modality: 0,
             hotkey: 0,
             coldkey: 0,
+            coldkey_confirmed: false,
+            transport_key: None,
         };
         // Let's call an emit.
         let total_emission:u64 = SubtensorModule::emit_for_neuron(&neuron);
@@ -164,7 +168,7 @@ fn test_many_with_weights() {
         let n = 25;
         let mut neurons: Vec<NeuronMetadata<u64>> = vec![];
         for i in 0..n {
-		neurons.push(subscribe_neuron(i as u64, 10, 666, 4, 0, 66));
+		neurons.push(subscribe_neuron(i as u64, 10, 666 + i as u16, 4, 0, 66));
         }
         let mut stakes = vec![];
         for (_, _) in neurons.iter().enumerate(){
@@ -264,7 +268,7 @@ fn test_emission_after_many_blocks() {
 
         // Subscribe n neurons
         for i in 0..n {
-                neurons.push(subscribe_neuron(i as u64, 10, 666, 4, 0, 66));
+                neurons.push(subscribe_neuron(i as u64, 10, 666 + i as u16, 4, 0, 66));
         }
         let mut stakes = vec![];
 
@@ -536,6 +540,95 @@ fn test_update_pending_emission_for_neuron_ok() {
 }
 
 
+/************************************************************
+	emission::update_pending_emissions() tests
+************************************************************/
+// Accrues the block reward of the current block the way update_pending_emissions did before
+// the reward per stake accumulator: a truncated share of the block reward per active neuron.
+fn accrue_per_block(uids: &Vec<u64>, expected: &mut Vec<u64>) {
+        let block_reward = SubtensorModule::get_reward_for_current_block();
+        let total_stake = SubtensorModule::get_total_stake();
+        if total_stake == 0 { return; }
+
+        for (i, uid) in uids.iter().enumerate() {
+                let stake = SubtensorModule::get_stake_of_neuron_hotkey_account_by_uid(*uid);
+                if stake == 0 || SubtensorModule::is_neuron_inactive(*uid) { continue; }
+                let stake_fraction = SubtensorModule::calulate_stake_fraction(stake, total_stake);
+                expected[i] += SubtensorModule::calculate_new_emission(block_reward, stake_fraction);
+        }
+}
+
+#[test]
+fn test_update_pending_emissions_is_lazy() {
+	new_test_ext().execute_with(|| {
+        let neurons: Vec<NeuronMetadata<u64>> = (0..8).map(|hotkey| subscribe_ok_neuron(hotkey, 66)).collect();
+        for neuron in neurons.iter() {
+                SubtensorModule::add_stake_to_neuron_hotkey_account(neuron.uid, 1_000_000_000);
+        }
+
+        // --- Blocks do not write the pending emission of any neuron, it accrues lazily.
+        run_to_block(8);
+        for neuron in neurons.iter() {
+                assert_eq!(SubtensorModule::pending_emission(neuron.uid), 0);
+                assert_eq!(SubtensorModule::get_pending_emission_for_neuron(neuron.uid), 500_000_000);
+        }
+
+        // --- A stake change settles the accrued emission into PendingEmission.
+        SubtensorModule::add_stake_to_neuron_hotkey_account(neurons[0].uid, 1);
+        assert_eq!(SubtensorModule::pending_emission(neurons[0].uid), 500_000_000);
+        assert_eq!(SubtensorModule::get_pending_emission_for_neuron(neurons[0].uid), 500_000_000);
+
+        // --- A block costs the same whatever the size of the metagraph.
+        assert_eq!(SubtensorModule::update_pending_emissions(), 1);
+	});
+}
+
+#[test]
+fn test_lazy_accrual_matches_per_block_accrual_across_stake_changes() {
+	new_test_ext().execute_with(|| {
+        InactivityThreshold::set(20);
+        let mut rng = StdRng::seed_from_u64(25);
+        let neurons: Vec<NeuronMetadata<u64>> = (0..5).map(|hotkey| subscribe_ok_neuron(hotkey, 66)).collect();
+        let uids: Vec<u64> = neurons.iter().map(|neuron| neuron.uid).collect();
+        let mut expected: Vec<u64> = vec![0; uids.len()];
+        let mut blocks_since_reset: Vec<u64> = vec![0; uids.len()];
+
+        for (i, uid) in uids.iter().enumerate() {
+                SubtensorModule::add_stake_to_neuron_hotkey_account(*uid, rng.gen_range(0, 1_000_000_000) * (i as u64 + 1));
+        }
+
+        for block in 1..=200 {
+                run_to_block(block);
+                accrue_per_block(&uids, &mut expected);
+
+                for (i, uid) in uids.iter().enumerate() {
+                        blocks_since_reset[i] += 1;
+                        let stake = SubtensorModule::get_stake_of_neuron_hotkey_account_by_uid(*uid);
+                        match rng.gen_range(0, 8) {
+                                0 => SubtensorModule::add_stake_to_neuron_hotkey_account(*uid, rng.gen_range(1, 1_000_000_000)),
+                                1 => SubtensorModule::remove_stake_from_neuron_hotkey_account(*uid, rng.gen_range(0, stake + 1)),
+                                2 => {
+                                        // --- The emission changes the stake of the destinations and resets the pending emission.
+                                        assert!(close(SubtensorModule::get_pending_emission_for_neuron(*uid).to_num::<u64>(), expected[i], blocks_since_reset[i] + 2));
+                                        SubtensorModule::emit_for_neuron(&neurons[i]);
+                                        expected[i] = 0;
+                                        blocks_since_reset[i] = 0;
+                                }
+                                3 => {
+                                        SubtensorModule::update_last_update_for_neuron(*uid);
+                                        SubtensorModule::reactivate_neuron(*uid);
+                                }
+                                _ => {}
+                        }
+                }
+        }
+
+        for (i, uid) in uids.iter().enumerate() {
+                assert!(close(SubtensorModule::get_pending_emission_for_neuron(*uid).to_num::<u64>(), expected[i], blocks_since_reset[i] + 2));
+        }
+	});
+}
+
 pub fn close(x:u64, y:u64, d:u64) -> bool {
     if x > y {
         if x - y < d {
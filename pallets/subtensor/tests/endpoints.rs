@@ -0,0 +1,244 @@
+use pallet_subtensor::{Error, EndpointProtocol, NeuronEndpoint, Endpoints};
+use frame_support::{assert_ok, traits::Get};
+use frame_system::Trait;
+mod mock;
+use mock::*;
+use pallet_subtensor::{Call as SubtensorCall};
+use frame_support::dispatch::{GetDispatchInfo, DispatchInfo};
+use frame_support::weights::{DispatchClass, Pays};
+
+/********************************************
+	endpoints::do_add_endpoint() tests
+*********************************************/
+#[test]
+fn fee_and_weight_of_add_endpoint() {
+	new_test_ext().execute_with(|| {
+		let call = SubtensorCall::add_endpoint(ipv4(8,8,8,8), 9090, 4, EndpointProtocol::Prometheus, 1);
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: <Test as Trait>::DbWeight::get().reads_writes(9 + MaxIpRangeOverrides::get() as u64, 6),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::No
+		});
+	});
+}
+
+#[test]
+fn test_subscribe_sets_primary_endpoint() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(1, 667);
+		let endpoints = SubtensorModule::get_endpoints_for_neuron(neuron.uid);
+		assert_eq!(endpoints, vec![NeuronEndpoint { ip: ipv4(8,8,8,8), port: neuron.port, ip_type: 4, protocol: EndpointProtocol::Grpc, version: 0 }]);
+	});
+}
+
+#[test]
+fn test_add_endpoint_ok() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, 667);
+
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 9090, 4, EndpointProtocol::Prometheus, 2));
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv6(0x2001,0x4860,0,0,0,0,0,0x8888), 8080, 6, EndpointProtocol::Http, 1));
+
+		let endpoints = SubtensorModule::get_endpoints_for_neuron(neuron.uid);
+		assert_eq!(endpoints.len(), 3);
+		assert_eq!(endpoints[1], NeuronEndpoint { ip: ipv4(8,8,8,8), port: 9090, ip_type: 4, protocol: EndpointProtocol::Prometheus, version: 2 });
+		assert_eq!(endpoints[2].protocol, EndpointProtocol::Http);
+		assert_eq!(endpoints[2].ip_type, 6);
+	});
+}
+
+#[test]
+fn test_add_endpoint_not_active() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,8), 9090, 4, EndpointProtocol::Http, 1);
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+#[test]
+fn test_add_endpoint_applies_ip_rules() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		subscribe_ok_neuron(hotkey_account_id, 667);
+
+		let result = SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(127,0,0,1), 9090, 4, EndpointProtocol::Http, 1);
+		assert_eq!(result, Err(Error::<Test>::LoopbackIpAddress.into()));
+
+		let result = SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(192,168,0,1), 9090, 4, EndpointProtocol::Http, 1);
+		assert_eq!(result, Err(Error::<Test>::PrivateIpAddress.into()));
+
+		let result = SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 9090, 5, EndpointProtocol::Http, 1);
+		assert_eq!(result, Err(Error::<Test>::InvalidIpType.into()));
+	});
+}
+
+#[test]
+fn test_add_endpoint_duplicate() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, 667);
+
+		// --- The subscription endpoint already serves grpc on this ip and port.
+		let result = SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), neuron.port, 4, EndpointProtocol::Grpc, 1);
+		assert_eq!(result, Err(Error::<Test>::DuplicateEndpoint.into()));
+
+		// --- A different protocol on the same address is allowed.
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), neuron.port, 4, EndpointProtocol::Http, 1));
+	});
+}
+
+#[test]
+fn test_add_endpoint_same_address_other_ip_type_is_not_duplicate() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, 667);
+
+		// --- The same encoded address and port as an ipv6 address is a different endpoint.
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), neuron.port, 6, EndpointProtocol::Grpc, 1));
+		let endpoints = SubtensorModule::get_endpoints_for_neuron(neuron.uid);
+		assert_eq!(endpoints.len(), 2);
+		assert_eq!(endpoints[1].ip_type, 6);
+	});
+}
+
+#[test]
+fn test_add_endpoint_too_many() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, 667);
+
+		let max = MaxEndpointsPerNeuron::get();
+		for i in 1..max {
+			assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 9000 + i as u16, 4, EndpointProtocol::Http, 1));
+		}
+		assert_eq!(SubtensorModule::get_endpoints_for_neuron(neuron.uid).len() as u32, max);
+
+		let result = SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 8999, 4, EndpointProtocol::Http, 1);
+		assert_eq!(result, Err(Error::<Test>::TooManyEndpoints.into()));
+	});
+}
+
+/********************************************
+	endpoints::do_remove_endpoint() tests
+*********************************************/
+#[test]
+fn fee_and_weight_of_remove_endpoint() {
+	new_test_ext().execute_with(|| {
+		let call = SubtensorCall::remove_endpoint(1);
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: <Test as Trait>::DbWeight::get().reads_writes(9, 6),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::No
+		});
+	});
+}
+
+#[test]
+fn test_remove_endpoint_ok() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, 667);
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 9090, 4, EndpointProtocol::Prometheus, 1));
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 8080, 4, EndpointProtocol::Http, 1));
+
+		assert_ok!(SubtensorModule::remove_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), 1));
+
+		let endpoints = SubtensorModule::get_endpoints_for_neuron(neuron.uid);
+		assert_eq!(endpoints.len(), 2);
+		assert_eq!(endpoints[0].protocol, EndpointProtocol::Grpc);
+		assert_eq!(endpoints[1].protocol, EndpointProtocol::Http);
+	});
+}
+
+#[test]
+fn test_remove_primary_endpoint_fails() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		subscribe_ok_neuron(hotkey_account_id, 667);
+		let result = SubtensorModule::remove_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), 0);
+		assert_eq!(result, Err(Error::<Test>::PrimaryEndpointNotRemovable.into()));
+	});
+}
+
+#[test]
+fn test_remove_endpoint_invalid_index() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		subscribe_ok_neuron(hotkey_account_id, 667);
+		let result = SubtensorModule::remove_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), 1);
+		assert_eq!(result, Err(Error::<Test>::InvalidEndpointIndex.into()));
+	});
+}
+
+#[test]
+fn test_add_and_remove_endpoint_respect_update_cooldown() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		assert_ok!(SubtensorModule::set_subscription_rate_limits(<<Test as Trait>::Origin>::root(), None, None, Some(5)));
+		let neuron = subscribe_ok_neuron(hotkey_account_id, 667);
+
+		let result = SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 9090, 4, EndpointProtocol::Prometheus, 1);
+		assert_eq!(result, Err(Error::<Test>::EndpointUpdateTooFast.into()));
+
+		run_to_block(5);
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 9090, 4, EndpointProtocol::Prometheus, 1));
+		assert_eq!(SubtensorModule::last_endpoint_update(neuron.uid), 5);
+
+		let result = SubtensorModule::remove_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), 1);
+		assert_eq!(result, Err(Error::<Test>::EndpointUpdateTooFast.into()));
+
+		run_to_block(10);
+		assert_ok!(SubtensorModule::remove_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), 1));
+		assert_eq!(SubtensorModule::last_endpoint_update(neuron.uid), 10);
+
+		// The endpoint changes also hold back the next resubscription.
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,9), neuron.port, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::EndpointUpdateTooFast.into()));
+	});
+}
+
+/********************************************
+	endpoints::set_primary_endpoint_for_neuron() tests
+*********************************************/
+#[test]
+fn test_resubscribe_updates_primary_endpoint_only() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, 667);
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 9090, 4, EndpointProtocol::Prometheus, 1));
+
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,4,4), 77, 4, 0, 667));
+
+		let endpoints = SubtensorModule::get_endpoints_for_neuron(neuron.uid);
+		assert_eq!(endpoints.len(), 2);
+		assert_eq!(endpoints[0].ip, ipv4(8,8,4,4));
+		assert_eq!(endpoints[0].port, 77);
+		assert_eq!(endpoints[1].protocol, EndpointProtocol::Prometheus);
+	});
+}
+
+#[test]
+fn test_endpoints_are_read_from_storage_only() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, 667);
+
+		// --- The neuron metadata is not used as a fallback, the migration seeds the storage.
+		Endpoints::remove(neuron.uid);
+		assert!(SubtensorModule::get_endpoints_for_neuron(neuron.uid).is_empty());
+	});
+}
+
+#[test]
+fn test_unsubscribe_removes_endpoints() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, 667);
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 9090, 4, EndpointProtocol::Prometheus, 1));
+
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), hotkey_account_id));
+		assert_eq!(Endpoints::contains_key(neuron.uid), false);
+		assert_eq!(SubtensorModule::get_endpoints_for_neuron(neuron.uid).len(), 0);
+	});
+}
@@ -0,0 +1,180 @@
+use pallet_subtensor::{Error, NeuronIdentity};
+use frame_support::{assert_ok, traits::Get};
+use frame_system::Trait;
+mod mock;
+use mock::*;
+use frame_support::sp_runtime::DispatchError;
+use pallet_subtensor::{Call as SubtensorCall};
+use frame_support::dispatch::{GetDispatchInfo, DispatchInfo};
+use frame_support::weights::{DispatchClass, Pays};
+
+fn set_display(coldkey: u64, hotkey: u64, display: &[u8]) -> frame_support::dispatch::DispatchResult {
+	SubtensorModule::set_identity(<<Test as Trait>::Origin>::signed(coldkey), hotkey, Some(display.to_vec()), None, None, None)
+}
+
+/********************************************
+	identity::do_set_identity() tests
+*********************************************/
+#[test]
+fn fee_and_weight_of_set_identity() {
+	new_test_ext().execute_with(|| {
+		let call = SubtensorCall::set_identity(1, Some(b"alice".to_vec()), None, None, None);
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: <Test as Trait>::DbWeight::get().reads_writes(4, 2),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::Yes
+		});
+	});
+}
+
+#[test]
+fn test_set_identity_ok() {
+	test_ext_with_balances(vec![(667, 1_000)]).execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+
+		assert_ok!(SubtensorModule::set_identity(<<Test as Trait>::Origin>::signed(667), 1,
+			Some(b"alice".to_vec()), Some(b"https://example.com".to_vec()), None, Some(b"2.0.1".to_vec())));
+
+		assert_eq!(SubtensorModule::identity(neuron.uid), Some(NeuronIdentity {
+			display: Some(b"alice".to_vec()),
+			url: Some(b"https://example.com".to_vec()),
+			contact: None,
+			software_version: Some(b"2.0.1".to_vec()),
+			deposit: IdentityDeposit::get(),
+		}));
+		assert_eq!(Balances::reserved_balance(&667), IdentityDeposit::get());
+		assert_eq!(SubtensorModule::get_coldkey_balance(&667), 1_000 - IdentityDeposit::get());
+	});
+}
+
+#[test]
+fn test_set_identity_update_keeps_deposit() {
+	test_ext_with_balances(vec![(667, 1_000)]).execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		assert_ok!(set_display(667, 1, b"alice"));
+		assert_ok!(set_display(667, 1, b"bob"));
+
+		assert_eq!(SubtensorModule::identity(neuron.uid).unwrap().display, Some(b"bob".to_vec()));
+		assert_eq!(Balances::reserved_balance(&667), IdentityDeposit::get());
+	});
+}
+
+#[test]
+fn test_set_identity_err_field_too_long() {
+	test_ext_with_balances(vec![(667, 1_000)]).execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		let long = vec![0u8; MaxIdentityFieldLength::get() as usize + 1];
+
+		let result = SubtensorModule::set_identity(<<Test as Trait>::Origin>::signed(667), 1, None, None, Some(long), None);
+		assert_eq!(result, Err(Error::<Test>::IdentityFieldTooLong.into()));
+		assert_eq!(SubtensorModule::identity(neuron.uid), None);
+		assert_eq!(Balances::reserved_balance(&667), 0);
+	});
+}
+
+#[test]
+fn test_set_identity_err_not_enough_balance() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		let result = set_display(667, 1, b"alice");
+		assert_eq!(result, Err(Error::<Test>::NotEnoughBalanceForIdentityDeposit.into()));
+		assert_eq!(SubtensorModule::identity(neuron.uid), None);
+	});
+}
+
+#[test]
+fn test_set_identity_err_non_associated_coldkey() {
+	test_ext_with_balances(vec![(668, 1_000)]).execute_with(|| {
+		let _neuron = subscribe_ok_confirmed_neuron(1, 667);
+		let result = set_display(668, 1, b"alice");
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+	});
+}
+
+#[test]
+fn test_set_identity_err_coldkey_not_confirmed() {
+	test_ext_with_balances(vec![(667, 1_000)]).execute_with(|| {
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,8), 66, 4, 0, 667));
+		let result = set_display(667, 1, b"alice");
+		assert_eq!(result, Err(Error::<Test>::ColdkeyNotConfirmed.into()));
+	});
+}
+
+#[test]
+fn test_set_identity_err_not_active() {
+	new_test_ext().execute_with(|| {
+		let result = set_display(667, 1, b"alice");
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+#[test]
+fn test_set_identity_failed_no_signature() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::set_identity(<<Test as Trait>::Origin>::none(), 1, None, None, None, None);
+		assert_eq!(result, Err(DispatchError::BadOrigin.into()));
+	});
+}
+
+/********************************************
+	identity::do_clear_identity() tests
+*********************************************/
+#[test]
+fn fee_and_weight_of_clear_identity() {
+	new_test_ext().execute_with(|| {
+		let call = SubtensorCall::clear_identity(1);
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: <Test as Trait>::DbWeight::get().reads_writes(4, 2),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::Yes
+		});
+	});
+}
+
+#[test]
+fn test_clear_identity_returns_deposit() {
+	test_ext_with_balances(vec![(667, 1_000)]).execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		assert_ok!(set_display(667, 1, b"alice"));
+
+		assert_ok!(SubtensorModule::clear_identity(<<Test as Trait>::Origin>::signed(667), 1));
+		assert_eq!(SubtensorModule::identity(neuron.uid), None);
+		assert_eq!(Balances::reserved_balance(&667), 0);
+		assert_eq!(SubtensorModule::get_coldkey_balance(&667), 1_000);
+	});
+}
+
+#[test]
+fn test_clear_identity_err_no_identity() {
+	new_test_ext().execute_with(|| {
+		let _neuron = subscribe_ok_confirmed_neuron(1, 667);
+		let result = SubtensorModule::clear_identity(<<Test as Trait>::Origin>::signed(667), 1);
+		assert_eq!(result, Err(Error::<Test>::NoIdentity.into()));
+	});
+}
+
+#[test]
+fn test_unsubscribe_returns_identity_deposit() {
+	test_ext_with_balances(vec![(667, 1_000)]).execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		assert_ok!(set_display(667, 1, b"alice"));
+
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(1), 1));
+		assert_eq!(SubtensorModule::identity(neuron.uid), None);
+		assert_eq!(Balances::reserved_balance(&667), 0);
+		assert_eq!(SubtensorModule::get_coldkey_balance(&667), 1_000);
+	});
+}
+
+#[test]
+fn test_swap_hotkey_keeps_identity() {
+	test_ext_with_balances(vec![(667, 1_000)]).execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		assert_ok!(set_display(667, 1, b"alice"));
+
+		assert_ok!(SubtensorModule::swap_hotkey(<<Test as Trait>::Origin>::signed(667), 1, 2));
+		assert_eq!(SubtensorModule::identity(neuron.uid).unwrap().display, Some(b"alice".to_vec()));
+		assert_ok!(SubtensorModule::clear_identity(<<Test as Trait>::Origin>::signed(667), 2));
+		assert_eq!(Balances::reserved_balance(&667), 0);
+	});
+}
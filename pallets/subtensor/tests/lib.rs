@@ -1,4 +1,4 @@
-use pallet_subtensor::{ChargeTransactionPayment, Error, CallType};
+use pallet_subtensor::{ChargeTransactionPayment, Error, CallType, SETTING_WEIGHTS_TOO_FAST};
 use frame_support::{assert_ok};
 
 mod mock;
@@ -13,6 +13,7 @@ use pallet_balances::{Call as BalanceCall};
 use pallet_sudo::{Call as SudoCall};
 use sp_runtime::transaction_validity::{InvalidTransaction, ValidTransaction};
 use frame_support::dispatch::GetDispatchInfo;
+use sp_core::H256;
 
 #[test]
 fn fee_from_emission_works() {
@@ -110,10 +111,10 @@ fn test_emission_low_priority_but_emission_goes_to_user() {
         let _neuron_1 = subscribe_neuron(hotkey_account_id_1, 10, 666, 4, 0, 66);
 
         let hotkey_account_id_2 = 2;
-        let neuron_2 = subscribe_neuron(hotkey_account_id_2, 10, 666, 4, 0, 66);
+        let neuron_2 = subscribe_neuron(hotkey_account_id_2, 10, 667, 4, 0, 66);
 
         let hotkey_account_id_3 = 3;
-        let neuron_3 = subscribe_neuron(hotkey_account_id_3, 10, 666, 4, 0, 66);
+        let neuron_3 = subscribe_neuron(hotkey_account_id_3, 10, 668, 4, 0, 66);
 
         let weight_uids = vec![neuron_3.uid];
         let weight_values = vec![u32::MAX];
@@ -135,7 +136,7 @@ fn test_emission_low_priority_but_emission_goes_to_user() {
 fn fee_from_emission_priority_with_neuron_and_adam() {
     new_test_ext().execute_with(|| {
         let adam_account_id = 0;
-        let _adam = subscribe_neuron(adam_account_id, 10, 666, 4, 0, 66);
+        let _adam = subscribe_neuron(adam_account_id, 10, 665, 4, 0, 66);
         let hotkey_account_id = 1;
         let neuron = subscribe_neuron(hotkey_account_id, 10, 666, 4, 0, 66);
         let weight_uids = vec![neuron.uid];
@@ -360,6 +361,114 @@ fn test_charge_transaction_payment_validate_set_weights_ok() {
     });
 }
 
+#[test]
+fn test_charge_transaction_payment_validate_set_weights_too_fast() {
+    let coldkey_id = 0;
+    let len = 200;
+
+    new_test_ext().execute_with(|| {
+        let adam = subscribe_ok_neuron(0, coldkey_id);
+        assert_ok!(SubtensorModule::set_weights_rate_limit(Origin::root(), Some(5)));
+        assert_ok!(SubtensorModule::set_weights(Origin::signed(0), vec![adam.uid], vec![u32::MAX]));
+
+        let call: mock::Call = SubtensorCall::set_weights(vec![0], vec![0]).into();
+        let info = call.get_dispatch_info();
+
+        let result = ChargeTransactionPayment::<Test>(PhantomData).validate(&0, &call, &info, len);
+        assert_eq!(result, Err(InvalidTransaction::Custom(SETTING_WEIGHTS_TOO_FAST).into()));
+
+        run_to_block(5);
+        assert!(ChargeTransactionPayment::<Test>(PhantomData).validate(&0, &call, &info, len).is_ok());
+    });
+}
+
+#[test]
+fn test_charge_transaction_payment_validate_reveal_weights_too_fast() {
+    let len = 200;
+
+    new_test_ext().execute_with(|| {
+        let adam = subscribe_ok_neuron(0, 0);
+        assert_ok!(SubtensorModule::set_weights_rate_limit(Origin::root(), Some(5)));
+        assert_ok!(SubtensorModule::set_weights(Origin::signed(0), vec![adam.uid], vec![u32::MAX]));
+
+        let call: mock::Call = SubtensorCall::reveal_weights(vec![0], vec![0], vec![], None).into();
+        let info = call.get_dispatch_info();
+
+        let result = ChargeTransactionPayment::<Test>(PhantomData).validate(&0, &call, &info, len);
+        assert_eq!(result, Err(InvalidTransaction::Custom(SETTING_WEIGHTS_TOO_FAST).into()));
+        let result = ChargeTransactionPayment::<Test>(PhantomData).pre_dispatch(&0, &call, &info, len);
+        assert_eq!(result.err(), Some(InvalidTransaction::Custom(SETTING_WEIGHTS_TOO_FAST).into()));
+
+        run_to_block(5);
+        let pre = ChargeTransactionPayment::<Test>(PhantomData).pre_dispatch(&0, &call, &info, len).unwrap();
+        assert_eq!(pre.0, CallType::SetWeights);
+    });
+}
+
+#[test]
+fn test_charge_transaction_payment_validate_update_weights_too_fast() {
+    let len = 200;
+
+    new_test_ext().execute_with(|| {
+        let adam = subscribe_ok_neuron(0, 0);
+        assert_ok!(SubtensorModule::set_weights_rate_limit(Origin::root(), Some(5)));
+        assert_ok!(SubtensorModule::set_weights(Origin::signed(0), vec![adam.uid], vec![u32::MAX]));
+
+        let call: mock::Call = SubtensorCall::update_weights(vec![(adam.uid, 1)], None).into();
+        let info = call.get_dispatch_info();
+
+        let result = ChargeTransactionPayment::<Test>(PhantomData).validate(&0, &call, &info, len);
+        assert_eq!(result, Err(InvalidTransaction::Custom(SETTING_WEIGHTS_TOO_FAST).into()));
+        let result = ChargeTransactionPayment::<Test>(PhantomData).pre_dispatch(&0, &call, &info, len);
+        assert_eq!(result.err(), Some(InvalidTransaction::Custom(SETTING_WEIGHTS_TOO_FAST).into()));
+
+        run_to_block(5);
+        let pre = ChargeTransactionPayment::<Test>(PhantomData).pre_dispatch(&0, &call, &info, len).unwrap();
+        assert_eq!(pre.0, CallType::SetWeights);
+    });
+}
+
+#[test]
+fn test_charge_transaction_payment_validate_set_weights_versioned_too_fast() {
+    let len = 200;
+
+    new_test_ext().execute_with(|| {
+        let adam = subscribe_ok_neuron(0, 0);
+        assert_ok!(SubtensorModule::set_weights_rate_limit(Origin::root(), Some(5)));
+        assert_ok!(SubtensorModule::set_weights(Origin::signed(0), vec![adam.uid], vec![u32::MAX]));
+
+        let call: mock::Call = SubtensorCall::set_weights_versioned(vec![adam.uid], vec![u32::MAX], 0).into();
+        let info = call.get_dispatch_info();
+
+        let result = ChargeTransactionPayment::<Test>(PhantomData).validate(&0, &call, &info, len);
+        assert_eq!(result, Err(InvalidTransaction::Custom(SETTING_WEIGHTS_TOO_FAST).into()));
+
+        run_to_block(5);
+        let pre = ChargeTransactionPayment::<Test>(PhantomData).pre_dispatch(&0, &call, &info, len).unwrap();
+        assert_eq!(pre.0, CallType::SetWeights);
+    });
+}
+
+#[test]
+fn test_charge_transaction_payment_validate_commit_weights_too_fast() {
+    let len = 200;
+
+    new_test_ext().execute_with(|| {
+        let _adam = subscribe_ok_neuron(0, 0);
+        assert_ok!(SubtensorModule::set_weights_rate_limit(Origin::root(), Some(5)));
+        assert_ok!(SubtensorModule::commit_weights(Origin::signed(0), H256::zero()));
+
+        let call: mock::Call = SubtensorCall::commit_weights(H256::zero()).into();
+        let info = call.get_dispatch_info();
+
+        let result = ChargeTransactionPayment::<Test>(PhantomData).validate(&0, &call, &info, len);
+        assert_eq!(result, Err(InvalidTransaction::Custom(SETTING_WEIGHTS_TOO_FAST).into()));
+
+        run_to_block(5);
+        assert!(ChargeTransactionPayment::<Test>(PhantomData).validate(&0, &call, &info, len).is_ok());
+    });
+}
+
 #[test]
 fn test_charge_transaction_payment_validate_add_stake_ok() {
     let coldkey_id = 0;
@@ -367,7 +476,7 @@ fn test_charge_transaction_payment_validate_add_stake_ok() {
     let len = 200;
 
     test_ext_with_balances(vec![(coldkey_id, 100_000)]).execute_with(|| {
-        let _adam = subscribe_ok_neuron(0, coldkey_id);
+        let _adam = subscribe_ok_confirmed_neuron(0, coldkey_id);
 
         let call: mock::Call = SubtensorCall::add_stake(hotkey_id, 5_000).into();
         let info = call.get_dispatch_info();
@@ -388,7 +497,7 @@ fn test_charge_transaction_payment_validate_remove_stake_ok() {
     let len = 200;
 
     test_ext_with_balances(vec![(coldkey_id, 100_000)]).execute_with(|| {
-        let _adam = subscribe_ok_neuron(0, coldkey_id);
+        let _adam = subscribe_ok_confirmed_neuron(0, coldkey_id);
 
         let call: mock::Call = SubtensorCall::remove_stake(hotkey_id, 5_000).into();
         let info = call.get_dispatch_info();
@@ -466,6 +575,34 @@ fn pre_dispatch_works() {
     });
 }
 
+#[test]
+fn pre_dispatch_charges_weights_key_calls_to_the_neuron() {
+    new_test_ext().execute_with(|| {
+        let hotkey_account_id = 1;
+        let coldkey_account_id = 66;
+        let weights_key = 99;
+        let neuron = subscribe_confirmed_neuron(hotkey_account_id, 10, 666, 4, 0, coldkey_account_id);
+        assert_ok!(SubtensorModule::set_weights_key(Origin::signed(coldkey_account_id), hotkey_account_id, weights_key));
+        assert_ok!(SubtensorModule::set_weights(Origin::signed(weights_key), vec![neuron.uid], vec![u32::MAX]));
+        SubtensorModule::add_stake_to_neuron_hotkey_account(neuron.uid, 1000000000); // Add the stake.
+        let call = SubtensorCall::set_weights(vec![0], vec![0]).into();
+        let info = DispatchInfo::default();
+        let len = 10;
+
+        run_to_block(1);
+
+        let result = ChargeTransactionPayment::<Test>(PhantomData).pre_dispatch(&weights_key, &call, &info, len).unwrap();
+        assert_eq!(result.0, CallType::SetWeights);
+        assert_eq!(result.1, 5_000_000);
+        assert_eq!(result.2, hotkey_account_id);
+
+        let call = SubtensorCall::update_weights(vec![(0, 0)], None).into();
+        let result = ChargeTransactionPayment::<Test>(PhantomData).pre_dispatch(&weights_key, &call, &info, len).unwrap();
+        assert_eq!(result.0, CallType::SetWeights);
+        assert_eq!(result.2, hotkey_account_id);
+    });
+}
+
 #[test]
 fn post_dispatch_works() {
     new_test_ext().execute_with(|| {
@@ -571,7 +708,7 @@ fn test_sudo_call_does_not_pay_transaction_fee() {
     let sudo_key = 1;
 
     test_ext_with_balances(vec![(source_key_id, balance)]).execute_with(|| {
-        let adam = subscribe_ok_neuron(0, 7778);
+        let adam = subscribe_ok_confirmed_neuron(0, 7778);
 
         let call = Box::new(Call::SubtensorModule(SubtensorCall::add_stake(dest_key_id, amount)));
 
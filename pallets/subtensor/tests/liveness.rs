@@ -0,0 +1,162 @@
+use frame_support::{assert_ok, traits::Get};
+use frame_system::Trait;
+mod mock;
+use mock::*;
+
+/********************************************
+	liveness::update_last_update_for_neuron() tests
+*********************************************/
+#[test]
+fn test_last_update_on_subscribe_and_set_weights() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, 667);
+		assert_eq!(SubtensorModule::get_last_update_for_neuron(neuron.uid), 0);
+
+		run_to_block(3);
+		assert_ok!(SubtensorModule::set_weights(<<Test as Trait>::Origin>::signed(hotkey_account_id), vec![neuron.uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::get_last_update_for_neuron(neuron.uid), 3);
+
+		run_to_block(5);
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,9), 66, 4, 0, 667));
+		assert_eq!(SubtensorModule::get_last_update_for_neuron(neuron.uid), 5);
+	});
+}
+
+/********************************************
+	liveness::sweep_inactive_neurons() tests
+*********************************************/
+#[test]
+fn test_neuron_marked_inactive_after_threshold() {
+	new_test_ext().execute_with(|| {
+		InactivityThreshold::set(3);
+		let neuron = subscribe_ok_neuron(1, 667);
+
+		run_to_block(3);
+		assert_eq!(SubtensorModule::is_neuron_inactive(neuron.uid), false);
+
+		run_to_block(4);
+		assert_eq!(SubtensorModule::is_neuron_inactive(neuron.uid), true);
+	});
+}
+
+#[test]
+fn test_set_weights_keeps_neuron_active() {
+	new_test_ext().execute_with(|| {
+		InactivityThreshold::set(3);
+		let neuron = subscribe_ok_neuron(1, 667);
+
+		run_to_block(3);
+		assert_ok!(SubtensorModule::set_weights(<<Test as Trait>::Origin>::signed(1), vec![neuron.uid], vec![u32::MAX]));
+
+		run_to_block(6);
+		assert_eq!(SubtensorModule::is_neuron_inactive(neuron.uid), false);
+
+		run_to_block(7);
+		assert_eq!(SubtensorModule::is_neuron_inactive(neuron.uid), true);
+	});
+}
+
+#[test]
+fn test_commit_weights_keeps_neuron_active() {
+	new_test_ext().execute_with(|| {
+		InactivityThreshold::set(3);
+		let neuron = subscribe_ok_neuron(1, 667);
+
+		run_to_block(3);
+		assert_ok!(SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(1), sp_core::H256::zero()));
+		assert_eq!(SubtensorModule::get_last_update_for_neuron(neuron.uid), 3);
+
+		run_to_block(6);
+		assert_eq!(SubtensorModule::is_neuron_inactive(neuron.uid), false);
+	});
+}
+
+#[test]
+fn test_sweep_weighs_checked_and_deactivated_neurons() {
+	new_test_ext().execute_with(|| {
+		InactivityThreshold::set(3);
+		let _neuron = subscribe_ok_neuron(1, 667);
+		let db_weight = <Test as Trait>::DbWeight::get();
+
+		assert_eq!(SubtensorModule::sweep_inactive_neurons(), db_weight.reads_writes(2, 1) + db_weight.reads(3));
+
+		System::set_block_number(4);
+		assert_eq!(SubtensorModule::sweep_inactive_neurons(), db_weight.reads_writes(2, 1) + db_weight.reads(3) + db_weight.reads_writes(5, 3));
+	});
+}
+
+#[test]
+fn test_sweep_is_bounded_per_block() {
+	new_test_ext().execute_with(|| {
+		InactivityThreshold::set(1);
+		for hotkey in 0..5 {
+			let _neuron = subscribe_ok_neuron(hotkey, 667);
+		}
+
+		// Two uids are checked per block. At block 1 uids 0 and 1 are still within the threshold.
+		run_to_block(2);
+		let inactive: Vec<bool> = (0..5).map(|uid| SubtensorModule::is_neuron_inactive(uid)).collect();
+		assert_eq!(inactive, vec![false, false, true, true, false]);
+
+		run_to_block(3);
+		let inactive: Vec<bool> = (0..5).map(|uid| SubtensorModule::is_neuron_inactive(uid)).collect();
+		assert_eq!(inactive, vec![true, false, true, true, true]);
+
+		run_to_block(4);
+		let inactive: Vec<bool> = (0..5).map(|uid| SubtensorModule::is_neuron_inactive(uid)).collect();
+		assert_eq!(inactive, vec![true, true, true, true, true]);
+	});
+}
+
+#[test]
+fn test_inactive_neuron_does_not_accrue_pending_emission() {
+	new_test_ext().execute_with(|| {
+		InactivityThreshold::set(3);
+		let neuron = subscribe_ok_neuron(1, 667);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron.uid, 1_000_000_000);
+
+		run_to_block(4);
+		assert_eq!(SubtensorModule::is_neuron_inactive(neuron.uid), true);
+		let pending = SubtensorModule::get_pending_emission_for_neuron(neuron.uid);
+		assert!(pending > 0);
+
+		run_to_block(10);
+		assert_eq!(SubtensorModule::get_pending_emission_for_neuron(neuron.uid), pending);
+	});
+}
+
+#[test]
+fn test_subscribe_reactivates_neuron() {
+	new_test_ext().execute_with(|| {
+		InactivityThreshold::set(3);
+		let neuron = subscribe_ok_neuron(1, 667);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron.uid, 1_000_000_000);
+
+		run_to_block(4);
+		assert_eq!(SubtensorModule::is_neuron_inactive(neuron.uid), true);
+
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,9), 66, 4, 0, 667));
+		assert_eq!(SubtensorModule::is_neuron_inactive(neuron.uid), false);
+
+		// The neuron accrues pending emission again.
+		let pending = SubtensorModule::get_pending_emission_for_neuron(neuron.uid);
+		run_to_block(5);
+		assert!(SubtensorModule::get_pending_emission_for_neuron(neuron.uid) > pending);
+	});
+}
+
+#[test]
+fn test_unsubscribe_clears_liveness_state() {
+	new_test_ext().execute_with(|| {
+		InactivityThreshold::set(3);
+		let neuron = subscribe_ok_neuron(1, 667);
+
+		run_to_block(4);
+		assert_eq!(SubtensorModule::is_neuron_inactive(neuron.uid), true);
+
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(1), 1));
+		assert_eq!(SubtensorModule::is_neuron_inactive(neuron.uid), false);
+		assert_eq!(SubtensorModule::get_last_update_for_neuron(neuron.uid), 0);
+	});
+}
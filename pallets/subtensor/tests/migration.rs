@@ -0,0 +1,213 @@
+use pallet_subtensor::{Neurons, Stake, Modalities, ModalityUids, StorageVersion, Releases, EndpointProtocol, Endpoints, Weights, NEURON_METADATA_VERSION};
+use frame_support::storage::{unhashed, StorageMap, StorageValue, StorageDoubleMap};
+use frame_support::storage::migration::{put_storage_value, have_storage_value};
+use frame_support::traits::OnRuntimeUpgrade;
+use codec::{Encode, Decode};
+mod mock;
+use mock::*;
+
+// The NeuronMetadata layout before Releases::V2_0_0.
+#[derive(Encode, Decode)]
+struct LegacyNeuronMetadata {
+	ip: u128,
+	port: u16,
+	ip_type: u8,
+	uid: u64,
+	modality: u8,
+	hotkey: u64,
+	coldkey: u64,
+}
+
+fn insert_legacy_neuron(uid: u64, hotkey: u64, coldkey: u64, port: u16) {
+	let legacy = LegacyNeuronMetadata {
+		ip: ipv4(8,8,8,8),
+		port: port,
+		ip_type: 4,
+		uid: uid,
+		modality: 0,
+		hotkey: hotkey,
+		coldkey: coldkey,
+	};
+	unhashed::put(&Neurons::<Test>::hashed_key_for(uid), &legacy);
+	pallet_subtensor::Active::<Test>::insert(hotkey, uid);
+}
+
+// The NeuronMetadata layout at Releases::V2_0_0.
+#[derive(Encode, Decode)]
+struct NeuronMetadataV2 {
+	version: u8,
+	ip: u128,
+	port: u16,
+	ip_type: u8,
+	uid: u64,
+	modality: u8,
+	hotkey: u64,
+	coldkey: u64,
+	coldkey_confirmed: bool,
+}
+
+/********************************************
+	migration::migrate_to_v2() tests
+*********************************************/
+#[test]
+fn test_genesis_sets_latest_storage_version() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(StorageVersion::get(), Releases::V4_0_0);
+	});
+}
+
+#[test]
+fn test_migrate_legacy_neurons() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::put(Releases::V1_0_0);
+		Modalities::remove(0);
+		insert_legacy_neuron(0, 1, 10, 66);
+		insert_legacy_neuron(1, 2, 10, 67);
+		Stake::insert(0, 1000);
+		Stake::insert(1, 500);
+
+		SubtensorModule::on_runtime_upgrade();
+
+		let neuron = SubtensorModule::get_neuron_for_uid(0);
+		assert_eq!(neuron.version, NEURON_METADATA_VERSION);
+		assert_eq!(neuron.ip, ipv4(8,8,8,8));
+		assert_eq!(neuron.port, 66);
+		assert_eq!(neuron.ip_type, 4);
+		assert_eq!(neuron.uid, 0);
+		assert_eq!(neuron.modality, 0);
+		assert_eq!(neuron.hotkey, 1);
+		assert_eq!(neuron.coldkey, 10);
+		assert_eq!(neuron.coldkey_confirmed, true);
+		assert_eq!(neuron.transport_key, None);
+		assert_eq!(SubtensorModule::get_neuron_for_hotkey(&2).port, 67);
+
+		// --- The coldkey, modality and endpoint storage is built for the legacy neurons.
+		let mut uids = SubtensorModule::get_uids_for_coldkey(&10);
+		uids.sort();
+		assert_eq!(uids, vec![0, 1]);
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&10), 1500);
+		assert!(ModalityUids::contains_key(0, 0));
+		assert!(ModalityUids::contains_key(0, 1));
+		assert_eq!(Modalities::get(0), Some(b"TEXT".to_vec()));
+		assert!(Endpoints::contains_key(0));
+		assert!(Endpoints::contains_key(1));
+		let endpoints = SubtensorModule::get_endpoints_for_neuron(1);
+		assert_eq!(endpoints.len(), 1);
+		assert_eq!(endpoints[0].port, 67);
+		assert_eq!(endpoints[0].protocol, EndpointProtocol::Grpc);
+
+		assert_eq!(StorageVersion::get(), Releases::V4_0_0);
+	});
+}
+
+#[test]
+fn test_migrated_coldkey_can_stake() {
+	test_ext_with_balances(vec![(10, 1_000_000_000)]).execute_with(|| {
+		StorageVersion::put(Releases::V1_0_0);
+		insert_legacy_neuron(0, 1, 10, 66);
+
+		SubtensorModule::on_runtime_upgrade();
+
+		assert!(SubtensorModule::add_stake(<<Test as frame_system::Trait>::Origin>::signed(10), 1, 1000).is_ok());
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&10), 1000);
+	});
+}
+
+#[test]
+fn test_migrate_clears_old_rate_limiter() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::put(Releases::V1_0_0);
+		put_storage_value(b"SubtensorModule", b"SubscriptionsThisBlock", &[], 5u32);
+		put_storage_value(b"SubtensorModule", b"LastSubscriptionBlock", &[], 3u64);
+
+		SubtensorModule::on_runtime_upgrade();
+
+		assert!(!have_storage_value(b"SubtensorModule", b"SubscriptionsThisBlock", &[]));
+		assert!(!have_storage_value(b"SubtensorModule", b"LastSubscriptionBlock", &[]));
+	});
+}
+
+#[test]
+fn test_migrate_is_noop_when_up_to_date() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron.uid, 1000);
+
+		SubtensorModule::on_runtime_upgrade();
+
+		// --- The coldkey storage is not built a second time.
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&667), 1000);
+		assert_eq!(SubtensorModule::get_uids_for_coldkey(&667), vec![neuron.uid]);
+	});
+}
+
+/********************************************
+	migration::migrate_to_v3() tests
+*********************************************/
+#[test]
+fn test_migrate_v2_neurons() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::put(Releases::V2_0_0);
+		let v2 = NeuronMetadataV2 {
+			version: 1,
+			ip: ipv4(8,8,8,8),
+			port: 66,
+			ip_type: 4,
+			uid: 0,
+			modality: 0,
+			hotkey: 1,
+			coldkey: 667,
+			coldkey_confirmed: false,
+		};
+		unhashed::put(&Neurons::<Test>::hashed_key_for(0), &v2);
+
+		SubtensorModule::on_runtime_upgrade();
+
+		let neuron = SubtensorModule::get_neuron_for_uid(0);
+		assert_eq!(neuron.version, NEURON_METADATA_VERSION);
+		assert_eq!(neuron.port, 66);
+		assert_eq!(neuron.hotkey, 1);
+		assert_eq!(neuron.coldkey, 667);
+		assert_eq!(neuron.coldkey_confirmed, false);
+		assert_eq!(neuron.transport_key, None);
+		assert_eq!(StorageVersion::get(), Releases::V4_0_0);
+	});
+}
+
+/********************************************
+	migration::migrate_to_v4() tests
+*********************************************/
+#[test]
+fn test_migrate_weights_into_sorted_edges() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::put(Releases::V3_0_0);
+		put_storage_value(b"SubtensorModule", b"WeightUids", &0u64.encode(), vec![3u64, 1, 2]);
+		put_storage_value(b"SubtensorModule", b"WeightVals", &0u64.encode(), vec![30u32, 10, 20]);
+
+		// --- Drifted rows drop the uids without a value.
+		put_storage_value(b"SubtensorModule", b"WeightUids", &1u64.encode(), vec![5u64, 4]);
+		put_storage_value(b"SubtensorModule", b"WeightVals", &1u64.encode(), vec![50u32]);
+
+		SubtensorModule::on_runtime_upgrade();
+
+		assert_eq!(Weights::get(0), vec![(1, 10), (2, 20), (3, 30)]);
+		assert_eq!(Weights::get(1), vec![(5, 50)]);
+		assert_eq!(SubtensorModule::get_weight_for_edge(0, 2), 20);
+		assert!(!have_storage_value(b"SubtensorModule", b"WeightUids", &0u64.encode()));
+		assert!(!have_storage_value(b"SubtensorModule", b"WeightVals", &1u64.encode()));
+		assert_eq!(StorageVersion::get(), Releases::V4_0_0);
+	});
+}
+
+#[test]
+fn test_migrate_weights_is_noop_when_up_to_date() {
+	new_test_ext().execute_with(|| {
+		Weights::insert(0, vec![(1u64, 10u32)]);
+		put_storage_value(b"SubtensorModule", b"WeightUids", &0u64.encode(), vec![2u64]);
+		put_storage_value(b"SubtensorModule", b"WeightVals", &0u64.encode(), vec![20u32]);
+
+		SubtensorModule::on_runtime_upgrade();
+
+		assert_eq!(Weights::get(0), vec![(1, 10)]);
+	});
+}
@@ -139,7 +139,21 @@ impl pallet_subtensor::Trait for Test {
 	type Event = ();
 	type Currency = Balances;
 	type TransactionByteFee = TransactionByteFee;
-
+	type MaxNeurons = MaxNeurons;
+	type ImmunityPeriod = ImmunityPeriod;
+	type MaxRegistrationsPerBlock = MaxRegistrationsPerBlock;
+	type MaxUpdatesPerBlock = MaxUpdatesPerBlock;
+	type UpdateCooldown = UpdateCooldown;
+	type InactivityThreshold = InactivityThreshold;
+	type MaxLivenessChecksPerBlock = MaxLivenessChecksPerBlock;
+	type MaxEndpointsPerNeuron = MaxEndpointsPerNeuron;
+	type MaxIpRangeOverrides = MaxIpRangeOverrides;
+	type IdentityDeposit = IdentityDeposit;
+	type MaxIdentityFieldLength = MaxIdentityFieldLength;
+	type MaxWeightsPerNeuron = MaxWeightsPerNeuron;
+	type WeightsSetRateLimit = WeightsSetRateLimit;
+	type WeightsRevealWindow = WeightsRevealWindow;
+	type MaxWeightPrunesPerBlock = MaxWeightPrunesPerBlock;
 }
 
 impl pallet_sudo::Trait for Test {
@@ -149,6 +163,53 @@ impl pallet_sudo::Trait for Test {
 
 parameter_types! {
 	pub const TransactionByteFee: Balance = 100;
+	pub const ImmunityPeriod: BlockNumber = 10;
+	pub const MaxRegistrationsPerBlock: u32 = 25;
+	pub const MaxUpdatesPerBlock: u32 = 25;
+	pub const UpdateCooldown: BlockNumber = 0;
+	pub const MaxLivenessChecksPerBlock: u64 = 2;
+	pub const MaxEndpointsPerNeuron: u32 = 4;
+	pub const MaxIpRangeOverrides: u32 = 2;
+	pub const IdentityDeposit: Balance = 100;
+	pub const MaxIdentityFieldLength: u32 = 32;
+	pub const MaxWeightsPerNeuron: u32 = 32;
+	pub const WeightsSetRateLimit: BlockNumber = 0;
+	pub const WeightsRevealWindow: BlockNumber = 5;
+	pub const MaxWeightPrunesPerBlock: u64 = 2;
+}
+
+thread_local! {
+	static MAX_NEURONS: RefCell<u64> = RefCell::new(1000);
+}
+
+pub struct MaxNeurons;
+impl MaxNeurons {
+	#[allow(dead_code)]
+	pub fn set(max_neurons: u64) {
+		MAX_NEURONS.with(|v| *v.borrow_mut() = max_neurons);
+	}
+}
+impl frame_support::traits::Get<u64> for MaxNeurons {
+	fn get() -> u64 {
+		MAX_NEURONS.with(|v| *v.borrow())
+	}
+}
+
+thread_local! {
+	static INACTIVITY_THRESHOLD: RefCell<BlockNumber> = RefCell::new(10_000);
+}
+
+pub struct InactivityThreshold;
+impl InactivityThreshold {
+	#[allow(dead_code)]
+	pub fn set(inactivity_threshold: BlockNumber) {
+		INACTIVITY_THRESHOLD.with(|v| *v.borrow_mut() = inactivity_threshold);
+	}
+}
+impl frame_support::traits::Get<BlockNumber> for InactivityThreshold {
+	fn get() -> BlockNumber {
+		INACTIVITY_THRESHOLD.with(|v| *v.borrow())
+	}
 }
 
 
@@ -251,10 +312,27 @@ pub type SubtensorModule = Module<Test>;
 ************************************************************/
 
 
+// The modalities registered at genesis in every test externality.
+pub fn default_modalities() -> Vec<(u8, Vec<u8>)> {
+	vec![(0, b"TEXT".to_vec())]
+}
+
 // Build genesis storage according to the mock runtime.
 #[allow(dead_code)]
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+
+	pallet_subtensor::GenesisConfig {
+		pending_emissions: vec![],
+		stake: vec![],
+		transaction_fee_pool: 0,
+		modalities: default_modalities()
+	}.assimilate_storage(&mut t)
+		.unwrap();
+
+	t.into()
 }
 
 #[allow(dead_code)]
@@ -267,6 +345,14 @@ pub fn test_ext_with_balances(balances : Vec<(u64, u128)>) -> sp_io::TestExterna
 		.assimilate_storage(&mut t)
 		.unwrap();
 
+	pallet_subtensor::GenesisConfig {
+		pending_emissions: vec![],
+		stake: vec![],
+		transaction_fee_pool: 0,
+		modalities: default_modalities()
+	}.assimilate_storage(&mut t)
+		.unwrap();
+
 	t.into()
 }
 
@@ -281,7 +367,8 @@ pub fn test_ext_with_pending_emissions(emissions : Vec<(u64, u64)>) -> sp_io::Te
 	pallet_subtensor::GenesisConfig {
 		pending_emissions: emissions,
 		stake: vec![],
-		transaction_fee_pool : 0
+		transaction_fee_pool : 0,
+		modalities: default_modalities()
 	}.assimilate_storage(&mut t)
 		.unwrap();
 
@@ -299,7 +386,8 @@ pub fn test_ext_with_stake(stake : Vec<(u64, u64)>) -> sp_io::TestExternalities
 	pallet_subtensor::GenesisConfig {
 		pending_emissions: vec![],
 		stake,
-		transaction_fee_pool: 0
+		transaction_fee_pool: 0,
+		modalities: default_modalities()
 	}.assimilate_storage(&mut t)
 		.unwrap();
 
@@ -316,7 +404,8 @@ pub fn test_ext_with_transaction_fee_pool(transaction_fee_pool : u64) -> sp_io::
 	pallet_subtensor::GenesisConfig {
 		pending_emissions: vec![],
 		stake: vec![],
-		transaction_fee_pool
+		transaction_fee_pool,
+		modalities: default_modalities()
 	}.assimilate_storage(&mut t)
 		.unwrap();
 
@@ -332,9 +421,31 @@ pub fn subscribe_neuron(hotkey_account_id : u64, ip: u128, port: u16, ip_type :
 	neuron
 }
 
+// Subscribes the neuron and lets the coldkey accept the hotkey, so the neuron can be
+// staked and managed by its coldkey in tests.
+#[allow(dead_code)]
+pub fn subscribe_confirmed_neuron(hotkey_account_id : u64, ip: u128, port: u16, ip_type : u8, modality: u8, coldkey_acount_id : u64) -> NeuronMetadata<u64> {
+	let neuron = subscribe_neuron(hotkey_account_id, ip, port, ip_type, modality, coldkey_acount_id);
+	if !neuron.coldkey_confirmed {
+		assert_ok!(SubtensorModule::accept_hotkey(<<Test as system::Trait>::Origin>::signed(coldkey_acount_id), hotkey_account_id));
+	}
+	SubtensorModule::get_neuron_for_hotkey(&hotkey_account_id)
+}
+
+// Each hotkey serves its own port, so test neurons never share an endpoint.
+#[allow(dead_code)]
+pub fn ok_port(hotkey_account_id : u64) -> u16 {
+	return (hotkey_account_id as u16).wrapping_add(66);
+}
+
 #[allow(dead_code)]
 pub fn subscribe_ok_neuron(hotkey_account_id : u64,  coldkey_account_id : u64) -> NeuronMetadata<u64> {
-	return subscribe_neuron(hotkey_account_id, ipv4(8,8,8,8), 66, 4, 0, coldkey_account_id );
+	return subscribe_neuron(hotkey_account_id, ipv4(8,8,8,8), ok_port(hotkey_account_id), 4, 0, coldkey_account_id );
+}
+
+#[allow(dead_code)]
+pub fn subscribe_ok_confirmed_neuron(hotkey_account_id : u64,  coldkey_account_id : u64) -> NeuronMetadata<u64> {
+	return subscribe_confirmed_neuron(hotkey_account_id, ipv4(8,8,8,8), ok_port(hotkey_account_id), 4, 0, coldkey_account_id );
 }
 
 #[allow(dead_code)]
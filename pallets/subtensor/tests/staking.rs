@@ -21,7 +21,7 @@ fn test_add_stake_dispatch_info_ok() {
         let call = Call::SubtensorModule(SubtensorCall::add_stake(account_id, stake));
 
 		assert_eq!(call.get_dispatch_info(), DispatchInfo {
-			weight: 0,
+			weight: SubtensorModule::max_prune_stale_weights_weight(),
 			class: DispatchClass::Normal,
 			pays_fee: Pays::Yes
 		});
@@ -42,7 +42,7 @@ fn test_add_stake_transaction_fee_ends_up_in_transaction_fee_pool() {
 
 	mock::test_ext_with_balances(balances).execute_with(|| {
 		// Register neuron_1
-		let test_neuron = subscribe_ok_neuron(test_neuron_hot_key, test_neuron_cold_key);
+		let test_neuron = subscribe_ok_confirmed_neuron(test_neuron_hot_key, test_neuron_cold_key);
 
 		// Verify start situation
         let start_balance = SubtensorModule::get_coldkey_balance(&test_neuron_cold_key);
@@ -76,7 +76,7 @@ fn test_add_stake_ok_no_emission() {
 		let coldkey_account_id = 55453;
 
 		// Subscribe neuron
-		let neuron = subscribe_neuron(hotkey_account_id, ip,port, ip_type, modality,  coldkey_account_id);
+		let neuron = subscribe_confirmed_neuron(hotkey_account_id, ip,port, ip_type, modality,  coldkey_account_id);
 
 		// Give it some $$$ in his coldkey balance
 		SubtensorModule::add_balance_to_coldkey_account(&coldkey_account_id, 10000);
@@ -182,7 +182,7 @@ fn test_add_stake_err_neuron_does_not_belong_to_coldkey() {
 		let hotkey_id = 54544;
 		let other_cold_key = 99498;
 
-		let _neuron = subscribe_neuron(hotkey_id, ipv4(8, 8, 8, 8), 66, 4, 0, coldkey_id);
+		let _neuron = subscribe_confirmed_neuron(hotkey_id, ipv4(8, 8, 8, 8), 66, 4, 0, coldkey_id);
 
 		// Perform the request which is signed by a different cold key
 		let result = SubtensorModule::add_stake(<<Test as Trait>::Origin>::signed(other_cold_key), hotkey_id, 1000);
@@ -190,6 +190,25 @@ fn test_add_stake_err_neuron_does_not_belong_to_coldkey() {
 	});
 }
 
+#[test]
+fn test_add_stake_err_coldkey_not_confirmed() {
+	let coldkey_id = 544;
+	let hotkey_id = 54544;
+
+	test_ext_with_balances(vec![(coldkey_id, 10_000)]).execute_with(|| {
+		// Subscribe without the coldkey accepting the hotkey.
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_id), ipv4(8, 8, 8, 8), 66, 4, 0, coldkey_id));
+
+		let result = SubtensorModule::add_stake(<<Test as Trait>::Origin>::signed(coldkey_id), hotkey_id, 1000);
+		assert_eq!(result, Err(Error::<Test>::ColdkeyNotConfirmed.into()));
+		assert_eq!(SubtensorModule::get_coldkey_balance(&coldkey_id), 10_000);
+
+		// Once the coldkey accepts the hotkey, it can stake.
+		assert_ok!(SubtensorModule::accept_hotkey(<<Test as Trait>::Origin>::signed(coldkey_id), hotkey_id));
+		assert_ok!(SubtensorModule::add_stake(<<Test as Trait>::Origin>::signed(coldkey_id), hotkey_id, 1000));
+	});
+}
+
 #[test]
 fn test_add_stake_err_not_enough_belance() {
 	new_test_ext().execute_with(|| {
@@ -197,7 +216,7 @@ fn test_add_stake_err_not_enough_belance() {
 		let hotkey_id = 54544;
 
 
-		let _neuron = subscribe_neuron(hotkey_id, ipv4(8, 8, 8, 8), 66, 4, 0, coldkey_id);
+		let _neuron = subscribe_confirmed_neuron(hotkey_id, ipv4(8, 8, 8, 8), 66, 4, 0, coldkey_id);
 
 		// Lets try to stake with 0 balance in cold key account
 		assert_eq!(SubtensorModule::get_coldkey_balance(&coldkey_id), 0);
@@ -220,7 +239,7 @@ fn test_remove_stake_dispatch_info_ok() {
 		let call = Call::SubtensorModule(SubtensorCall::remove_stake(account_id, stake));
 
 		assert_eq!(call.get_dispatch_info(), DispatchInfo {
-			weight: 0,
+			weight: SubtensorModule::max_prune_stale_weights_weight(),
 			class: DispatchClass::Normal,
 			pays_fee: Pays::Yes
 		});
@@ -234,8 +253,8 @@ fn test_remove_stake_ok_transaction_fee_ends_up_in_transaction_fee_pool() {
 	let hotkey_id = 1;
 
 	test_ext_with_balances(vec![(coldkey_id, initial_stake as u128)]).execute_with(|| {
-        let _adam = subscribe_ok_neuron(0,667);
-		let _neuron = subscribe_ok_neuron(hotkey_id, coldkey_id);
+        let _adam = subscribe_ok_confirmed_neuron(0,667);
+		let _neuron = subscribe_ok_confirmed_neuron(hotkey_id, coldkey_id);
 		assert_ok!(SubtensorModule::add_stake(Origin::signed(coldkey_id), hotkey_id, initial_stake));
 
 
@@ -259,7 +278,7 @@ fn test_remove_stake_ok_no_emission() {
 		let amount = 10000;
 
 		// Let's spin up a neuron
-		let neuron = subscribe_neuron(hotkey_account_id, ipv4(8,8,8,8), 66, 4, 0, coldkey_account_id);
+		let neuron = subscribe_confirmed_neuron(hotkey_account_id, ipv4(8,8,8,8), 66, 4, 0, coldkey_account_id);
 
 		// Some basic assertions
 		assert_eq!(SubtensorModule::get_total_stake(), 0);
@@ -287,9 +306,9 @@ fn test_remove_stake_ok_with_emission() {
 		let initial_amount = 20000; // This will be added before the function UT is called, to trigger an emit
 
 		// Add neurons
-		let _adam = subscribe_ok_neuron(0, coldkey_account_id);
-		let neuron_src = subscribe_ok_neuron(hotkey_neuron_src, coldkey_account_id);
-		let neuron_dest = subscribe_ok_neuron(hotkey_neuron_dest, coldkey_account_id);
+		let _adam = subscribe_ok_confirmed_neuron(0, coldkey_account_id);
+		let neuron_src = subscribe_ok_confirmed_neuron(hotkey_neuron_src, coldkey_account_id);
+		let neuron_dest = subscribe_ok_confirmed_neuron(hotkey_neuron_dest, coldkey_account_id);
 
 		// Set neuron_src weight to neuron_dest
 		let _ = SubtensorModule::set_weights(Origin::signed(hotkey_neuron_src), vec![neuron_dest.uid], vec![100]);
@@ -353,7 +372,7 @@ fn test_remove_stake_err_neuron_does_not_belong_to_coldkey() {
 		let hotkey_id = 54544;
 		let other_cold_key = 99498;
 
-		let _neuron = subscribe_ok_neuron(hotkey_id,coldkey_id);
+		let _neuron = subscribe_ok_confirmed_neuron(hotkey_id,coldkey_id);
 
 		// Perform the request which is signed by a different cold key
 		let result = SubtensorModule::remove_stake(<<Test as Trait>::Origin>::signed(other_cold_key), hotkey_id, 1000);
@@ -361,6 +380,22 @@ fn test_remove_stake_err_neuron_does_not_belong_to_coldkey() {
 	});
 }
 
+#[test]
+fn test_remove_stake_err_coldkey_not_confirmed() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 544;
+		let hotkey_id = 54544;
+
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_id), ipv4(8, 8, 8, 8), 66, 4, 0, coldkey_id));
+		let neuron = SubtensorModule::get_neuron_for_hotkey(&hotkey_id);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron.uid, 1000);
+
+		let result = SubtensorModule::remove_stake(<<Test as Trait>::Origin>::signed(coldkey_id), hotkey_id, 1000);
+		assert_eq!(result, Err(Error::<Test>::ColdkeyNotConfirmed.into()));
+		assert_eq!(SubtensorModule::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid), 1000);
+	});
+}
+
 #[test]
 fn test_remove_stake_no_enough_stake() {
 	new_test_ext().execute_with(|| {
@@ -368,7 +403,7 @@ fn test_remove_stake_no_enough_stake() {
 		let hotkey_id = 54544;
 		let amount = 10000;
 
-		let neuron = subscribe_ok_neuron(hotkey_id, coldkey_id);
+		let neuron = subscribe_ok_confirmed_neuron(hotkey_id, coldkey_id);
 
 		assert_eq!(SubtensorModule::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid), 0);
 
@@ -756,4 +791,85 @@ fn test_calculate_stake_fraction_for_neuron_no_neuron_stake() {
 		// This guy gets 0, because no stake
 		assert_eq!(SubtensorModule::calculate_stake_fraction_for_neuron(&neurons[1]), 0);
 	});
-}
\ No newline at end of file
+}
+/***********************************************************
+	staking coldkey index tests
+************************************************************/
+
+#[test]
+fn test_coldkey_index_tracks_confirmed_neurons() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let neuron_one = subscribe_ok_confirmed_neuron(1, coldkey_id);
+		let neuron_two = subscribe_ok_confirmed_neuron(2, coldkey_id);
+		let _other = subscribe_ok_confirmed_neuron(3, 668);
+
+		let mut uids = SubtensorModule::get_uids_for_coldkey(&coldkey_id);
+		uids.sort();
+		assert_eq!(uids, vec![neuron_one.uid, neuron_two.uid]);
+		assert_eq!(SubtensorModule::get_neurons_for_coldkey(&668).len(), 1);
+	});
+}
+
+#[test]
+fn test_coldkey_index_excludes_unconfirmed_neurons() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let hotkey_id = 1;
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_id), ipv4(8, 8, 8, 8), 66, 4, 0, coldkey_id));
+		let neuron = SubtensorModule::get_neuron_for_hotkey(&hotkey_id);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron.uid, 1000);
+
+		assert_eq!(SubtensorModule::get_uids_for_coldkey(&coldkey_id), Vec::<u64>::new());
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&coldkey_id), 0);
+
+		// Accepting the hotkey adds the neuron and its current stake.
+		assert_ok!(SubtensorModule::accept_hotkey(<<Test as Trait>::Origin>::signed(coldkey_id), hotkey_id));
+		assert_eq!(SubtensorModule::get_uids_for_coldkey(&coldkey_id), vec![neuron.uid]);
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&coldkey_id), 1000);
+	});
+}
+
+#[test]
+fn test_total_coldkey_stake_follows_add_and_remove_stake() {
+	let coldkey_id = 667;
+	test_ext_with_balances(vec![(coldkey_id, 10_000)]).execute_with(|| {
+		let _neuron_one = subscribe_ok_confirmed_neuron(1, coldkey_id);
+		let _neuron_two = subscribe_ok_confirmed_neuron(2, coldkey_id);
+
+		assert_ok!(SubtensorModule::add_stake(<<Test as Trait>::Origin>::signed(coldkey_id), 1, 6_000));
+		assert_ok!(SubtensorModule::add_stake(<<Test as Trait>::Origin>::signed(coldkey_id), 2, 4_000));
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&coldkey_id), 10_000);
+
+		assert_ok!(SubtensorModule::remove_stake(<<Test as Trait>::Origin>::signed(coldkey_id), 1, 2_500));
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&coldkey_id), 7_500);
+	});
+}
+
+#[test]
+fn test_coldkey_index_after_unsubscribe_and_swap() {
+	new_test_ext().execute_with(|| {
+		let coldkey_id = 667;
+		let neuron_one = subscribe_ok_confirmed_neuron(1, coldkey_id);
+		let neuron_two = subscribe_ok_confirmed_neuron(2, coldkey_id);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron_one.uid, 1000);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron_two.uid, 500);
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&coldkey_id), 1500);
+
+		// Swapping the hotkey keeps the uid under the same coldkey.
+		assert_ok!(SubtensorModule::swap_hotkey(<<Test as Trait>::Origin>::signed(coldkey_id), 2, 3));
+		let mut uids = SubtensorModule::get_uids_for_coldkey(&coldkey_id);
+		uids.sort();
+		assert_eq!(uids, vec![neuron_one.uid, neuron_two.uid]);
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&coldkey_id), 1500);
+
+		// Unsubscribing returns the stake and removes the uid.
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(coldkey_id), 1));
+		assert_eq!(SubtensorModule::get_uids_for_coldkey(&coldkey_id), vec![neuron_two.uid]);
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&coldkey_id), 500);
+
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(3), 3));
+		assert_eq!(SubtensorModule::get_uids_for_coldkey(&coldkey_id), Vec::<u64>::new());
+		assert_eq!(SubtensorModule::get_total_stake_for_coldkey(&coldkey_id), 0);
+	});
+}
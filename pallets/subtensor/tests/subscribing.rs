@@ -1,5 +1,5 @@
-use pallet_subtensor::{Error};
-use frame_support::{assert_ok};
+use pallet_subtensor::{Error, EndpointProtocol};
+use frame_support::{assert_ok, traits::Get};
 use frame_system::Trait;
 mod mock;
 use mock::*;
@@ -23,7 +23,7 @@ fn test_subscribe_ok_dispatch_info_ok() {
         let call = Call::SubtensorModule(SubtensorCall::subscribe(ip, port, ip_type, modality, coldkey_id));
 
 		assert_eq!(call.get_dispatch_info(), DispatchInfo {
-			weight: 0,
+			weight: <Test as Trait>::DbWeight::get().reads(4 * MaxNeurons::get() + MaxIpRangeOverrides::get() as u64) + SubtensorModule::max_prune_stale_weights_weight(),
 			class: DispatchClass::Normal,
 			pays_fee: Pays::No
 		});
@@ -101,16 +101,107 @@ fn test_subscriptions_per_block() {
 		let modality = 0;
 		let coldkey_account_id = 667; // N
 		for i in 0..= 24 {
-			assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(i), ip, port, ip_type, modality, coldkey_account_id));
+			assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(i), ip, port + i as u16, ip_type, modality, coldkey_account_id));
 		}
-		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(25), ip, port, ip_type, modality, coldkey_account_id);
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(25), ip, port + 25, ip_type, modality, coldkey_account_id);
 		assert_eq!(result, Err(Error::<Test>::ToManySubscriptionsThisBlock.into()));
 		run_to_block(1);
 		for i in 0..= 24 {
-			assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(26 + i), ip, port, ip_type, modality, coldkey_account_id));
+			assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(26 + i), ip, port + 26 + i as u16, ip_type, modality, coldkey_account_id));
+		}
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(52), ip, port + 52, ip_type, modality, coldkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::ToManySubscriptionsThisBlock.into()));
+	});
+}
+
+#[test]
+fn test_updates_do_not_count_towards_registrations() {
+	new_test_ext().execute_with(|| {
+		let coldkey_account_id = 667;
+		for i in 0..= 23 {
+			let _neuron = subscribe_ok_neuron(i, coldkey_account_id);
+		}
+
+		// Updates of active neurons are counted separately.
+		for i in 0..= 23 {
+			assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(i), ipv4(8,8,8,9), ok_port(i), 4, 0, coldkey_account_id));
+		}
+
+		// One registration is left in this block.
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(24), ipv4(8,8,8,8), ok_port(24), 4, 0, coldkey_account_id));
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(25), ipv4(8,8,8,8), ok_port(25), 4, 0, coldkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::ToManySubscriptionsThisBlock.into()));
+	});
+}
+
+#[test]
+fn test_updates_per_block() {
+	new_test_ext().execute_with(|| {
+		let coldkey_account_id = 667;
+		assert_ok!(SubtensorModule::set_subscription_rate_limits(<<Test as Trait>::Origin>::root(), None, Some(2), None));
+		for i in 0..3 {
+			let _neuron = subscribe_ok_neuron(i, coldkey_account_id);
 		}
-		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(52), ip, port, ip_type, modality, coldkey_account_id);
+
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(0), ipv4(8,8,8,9), ok_port(0), 4, 0, coldkey_account_id));
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,9), ok_port(1), 4, 0, coldkey_account_id));
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,9), ok_port(2), 4, 0, coldkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::TooManyUpdatesThisBlock.into()));
+		assert_eq!(SubtensorModule::get_neuron_for_hotkey(&2).ip, ipv4(8,8,8,8));
+
+		run_to_block(1);
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,9), ok_port(2), 4, 0, coldkey_account_id));
+	});
+}
+
+#[test]
+fn test_update_cooldown() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let coldkey_account_id = 667;
+		assert_ok!(SubtensorModule::set_subscription_rate_limits(<<Test as Trait>::Origin>::root(), None, None, Some(5)));
+		let neuron = subscribe_ok_neuron(hotkey_account_id, coldkey_account_id);
+		assert_eq!(SubtensorModule::last_endpoint_update(neuron.uid), 0);
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,9), 66, 4, 0, coldkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::EndpointUpdateTooFast.into()));
+
+		run_to_block(5);
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,9), 66, 4, 0, coldkey_account_id));
+		assert_eq!(SubtensorModule::last_endpoint_update(neuron.uid), 5);
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,10), 66, 4, 0, coldkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::EndpointUpdateTooFast.into()));
+	});
+}
+
+#[test]
+fn test_set_subscription_rate_limits_override_and_reset() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(SubtensorModule::get_max_registrations_per_block(), 25);
+		assert_ok!(SubtensorModule::set_subscription_rate_limits(<<Test as Trait>::Origin>::root(), Some(1), Some(3), Some(7)));
+		assert_eq!(SubtensorModule::get_max_registrations_per_block(), 1);
+		assert_eq!(SubtensorModule::get_max_updates_per_block(), 3);
+		assert_eq!(SubtensorModule::get_update_cooldown(), 7);
+
+		let _neuron = subscribe_ok_neuron(1, 667);
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,8), 66, 4, 0, 667);
 		assert_eq!(result, Err(Error::<Test>::ToManySubscriptionsThisBlock.into()));
+
+		// Removing the overrides restores the Trait constants.
+		assert_ok!(SubtensorModule::set_subscription_rate_limits(<<Test as Trait>::Origin>::root(), None, None, None));
+		assert_eq!(SubtensorModule::get_max_registrations_per_block(), 25);
+		assert_eq!(SubtensorModule::get_max_updates_per_block(), 25);
+		assert_eq!(SubtensorModule::get_update_cooldown(), 0);
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,8), 66, 4, 0, 667));
+	});
+}
+
+#[test]
+fn test_set_subscription_rate_limits_not_root() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::set_subscription_rate_limits(<<Test as Trait>::Origin>::signed(1), Some(1), None, None);
+		assert_eq!(result, Err(DispatchError::BadOrigin.into()));
 	});
 }
 
@@ -354,18 +445,69 @@ fn test_subscribe_failed_invalid_ip_type() {
 fn test_subscribe_failed_invalid_ip_address() {
 	new_test_ext().execute_with(|| {
 		let hotkey_account_id = 1;
-		let ip = ipv6(0,0,0,0,0,0,0,1); // Ipv6 localhost, invalid
+		let ip = ipv6(0,0,0,0,0,0,0,0); // Ipv6 unspecified, invalid
 		let ip_type = 6;
 		let port = 1337;
 		let modality = 0;
 		let coldkey_account_id = 667;
 
-		// This first subscription should succeed without problems
 		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ip, port, ip_type, modality, coldkey_account_id);
 		assert_eq!(result, Err(Error::<Test>::InvalidIpAddress.into()));
 	});
 }
 
+#[test]
+fn test_subscribe_failed_loopback_ip_address() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let coldkey_account_id = 667;
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv6(0,0,0,0,0,0,0,1), 1337, 6, 0, coldkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::LoopbackIpAddress.into()));
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(127,0,0,1), 1337, 4, 0, coldkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::LoopbackIpAddress.into()));
+	});
+}
+
+#[test]
+fn test_subscribe_failed_this_network_ip_address() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let coldkey_account_id = 667;
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(0,1,2,3), 1337, 4, 0, coldkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::ThisNetworkIpAddress.into()));
+	});
+}
+
+#[test]
+fn test_subscribe_failed_special_purpose_ip_addresses() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let coldkey_account_id = 667;
+		let cases = vec![
+			(4, ipv4(192,168,0,1), Error::<Test>::PrivateIpAddress),
+			(4, ipv4(100,64,0,1), Error::<Test>::SharedIpAddress),
+			(4, ipv4(169,254,0,1), Error::<Test>::LinkLocalIpAddress),
+			(4, ipv4(224,0,0,1), Error::<Test>::MulticastIpAddress),
+			(4, ipv4(203,0,113,1), Error::<Test>::DocumentationIpAddress),
+			(4, ipv4(240,0,0,1), Error::<Test>::ReservedIpAddress),
+			(6, ipv6(0xfd00,0,0,0,0,0,0,1), Error::<Test>::UniqueLocalIpAddress),
+			(6, ipv6(0xfe80,0,0,0,0,0,0,1), Error::<Test>::LinkLocalIpAddress),
+			(6, ipv6(0xff02,0,0,0,0,0,0,1), Error::<Test>::MulticastIpAddress),
+			(6, ipv6(0x2001,0x0db8,0,0,0,0,0,1), Error::<Test>::DocumentationIpAddress),
+			(6, ipv6(0,0,0,0,0,0xffff,0x0808,0x0808), Error::<Test>::Ipv4MappedIpAddress),
+		];
+
+		for (ip_type, ip, error) in cases {
+			let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ip, 1337, ip_type, 0, coldkey_account_id);
+			assert_eq!(result, Err(error.into()));
+		}
+		assert_eq!(SubtensorModule::get_neuron_count(), 0);
+	});
+}
+
 #[test]
 fn test_subscribe_failed_no_signature() {
 	new_test_ext().execute_with(|| {
@@ -424,6 +566,7 @@ fn test_add_neuron_to_metagraph_ok() {
 		assert_eq!(neuron.ip_type, ip_type);
 		assert_eq!(neuron.coldkey, coldkey);
 		assert_eq!(neuron.modality, modality);
+		assert_eq!(neuron.coldkey_confirmed, false);
 	});
 }
 
@@ -440,3 +583,819 @@ fn test_get_next_uid() {
 }
 
 
+
+/********************************************
+	subscribing::unsubscribe() tests
+*********************************************/
+#[test]
+fn test_unsubscribe_ok_dispatch_info_ok() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+        let call = Call::SubtensorModule(SubtensorCall::unsubscribe(hotkey_account_id));
+
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: SubtensorModule::max_prune_stale_weights_weight(),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::No
+		});
+	});
+}
+
+#[test]
+fn test_unsubscribe_by_hotkey_ok() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let coldkey_account_id = 667;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, coldkey_account_id);
+
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), hotkey_account_id));
+
+		// All the per-uid storage is gone.
+		assert_eq!(SubtensorModule::is_hotkey_active(&hotkey_account_id), false);
+		assert_eq!(SubtensorModule::is_uid_active(neuron.uid), false);
+		assert_eq!(SubtensorModule::has_hotkey_account(&neuron.uid), false);
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron), (vec![], vec![]));
+	});
+}
+
+#[test]
+fn test_unsubscribe_by_coldkey_returns_stake() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let coldkey_account_id = 667;
+		let stake = 10_000;
+		let neuron = subscribe_ok_confirmed_neuron(hotkey_account_id, coldkey_account_id);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron.uid, stake);
+		assert_eq!(SubtensorModule::get_total_stake(), stake);
+
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(coldkey_account_id), hotkey_account_id));
+
+		// The stake is back on the coldkey and removed from the total stake.
+		assert_eq!(SubtensorModule::get_coldkey_balance(&coldkey_account_id), stake as u128);
+		assert_eq!(SubtensorModule::get_total_stake(), 0);
+		assert_eq!(SubtensorModule::is_uid_active(neuron.uid), false);
+	});
+}
+
+#[test]
+fn test_unsubscribe_flushes_pending_emission() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let coldkey_account_id = 667;
+		let stake = 1_000_000_000;
+		let neuron = subscribe_ok_neuron(hotkey_account_id, coldkey_account_id);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron.uid, stake);
+
+		// The neuron holds all stake and only has a self weight, so it receives the full block reward.
+		run_to_block(1);
+		assert_eq!(SubtensorModule::get_pending_emission_for_neuron(neuron.uid), 500_000_000);
+
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), hotkey_account_id));
+
+		assert_eq!(SubtensorModule::get_coldkey_balance(&coldkey_account_id), 1_500_000_000);
+		assert_eq!(SubtensorModule::get_pending_emission_for_neuron(neuron.uid), 0);
+		assert_eq!(SubtensorModule::get_total_stake(), 0);
+	});
+}
+
+#[test]
+fn test_unsubscribe_err_not_active() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+
+		let result = SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), hotkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+#[test]
+fn test_unsubscribe_err_non_associated_coldkey() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let coldkey_account_id = 667;
+		let other_account_id = 668;
+		let _neuron = subscribe_ok_neuron(hotkey_account_id, coldkey_account_id);
+
+		let result = SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(other_account_id), hotkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+		assert_eq!(SubtensorModule::is_hotkey_active(&hotkey_account_id), true);
+	});
+}
+
+#[test]
+fn test_unsubscribe_failed_no_signature() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+
+		let result = SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::none(), hotkey_account_id);
+		assert_eq!(result, Err(DispatchError::BadOrigin.into()));
+	});
+}
+
+/********************************************
+	subscribing::get_uid_for_new_neuron() tests
+*********************************************/
+#[test]
+fn test_subscribe_err_metagraph_full_all_immune() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(2);
+		let _neuron_one = subscribe_ok_neuron(1, 667);
+		let _neuron_two = subscribe_ok_neuron(2, 667);
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(3), ipv4(8,8,8,8), 66, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::MetagraphFull.into()));
+		assert_eq!(SubtensorModule::is_hotkey_active(&3), false);
+	});
+}
+
+#[test]
+fn test_failed_subscription_does_not_count_towards_registrations() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(2);
+		assert_ok!(SubtensorModule::set_subscription_rate_limits(<<Test as Trait>::Origin>::root(), Some(3), None, None));
+		let _neuron_one = subscribe_ok_neuron(1, 667);
+		let _neuron_two = subscribe_ok_neuron(2, 667);
+
+		for hotkey in 3..10 {
+			let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey), ipv4(8,8,8,8), ok_port(hotkey), 4, 0, 667);
+			assert_eq!(result, Err(Error::<Test>::MetagraphFull.into()));
+		}
+
+		// The failed subscriptions left the last registration of this block available.
+		MaxNeurons::set(3);
+		let _neuron_three = subscribe_ok_neuron(3, 667);
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(4), ipv4(8,8,8,8), ok_port(4), 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::ToManySubscriptionsThisBlock.into()));
+	});
+}
+
+#[test]
+fn test_subscribe_replaces_lowest_stake_after_immunity_period() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(2);
+		let coldkey_account_id = 667;
+		let neuron_one = subscribe_ok_neuron(1, coldkey_account_id);
+		let neuron_two = subscribe_ok_neuron(2, coldkey_account_id);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron_one.uid, 10_000);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron_two.uid, 100);
+
+		// Move past the immunity period.
+		run_to_block(11);
+
+		let neuron_three = subscribe_ok_neuron(3, coldkey_account_id);
+
+		// The neuron with the lowest stake is replaced and its uid is reused.
+		assert_eq!(neuron_three.uid, neuron_two.uid);
+		assert_eq!(SubtensorModule::is_hotkey_active(&2), false);
+		assert_eq!(SubtensorModule::is_hotkey_active(&1), true);
+		assert_eq!(SubtensorModule::get_neuron_count(), 2);
+
+		// The new neuron starts from a clean slate.
+		assert_eq!(SubtensorModule::get_stake_of_neuron_hotkey_account_by_uid(neuron_three.uid), 0);
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_three), (vec![neuron_three.uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::get_block_at_registration_for_neuron(neuron_three.uid), 11);
+	});
+}
+
+#[test]
+fn test_subscribe_replace_ties_broken_by_last_emit() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(2);
+		let neuron_one = subscribe_ok_neuron(1, 667);
+		let neuron_two = subscribe_ok_neuron(2, 667);
+
+		// Both neurons have zero stake, but neuron one emits later.
+		run_to_block(5);
+		SubtensorModule::update_last_emit_for_neuron(neuron_one.uid);
+		run_to_block(11);
+
+		let neuron_three = subscribe_ok_neuron(3, 667);
+		assert_eq!(neuron_three.uid, neuron_two.uid);
+	});
+}
+
+#[test]
+fn test_subscribe_reuses_vacant_uid() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(2);
+		let neuron_one = subscribe_ok_neuron(1, 667);
+		let _neuron_two = subscribe_ok_neuron(2, 667);
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(1), 1));
+
+		// The vacant uid is reused, even though the other neuron is still immune.
+		let neuron_three = subscribe_ok_neuron(3, 667);
+		assert_eq!(neuron_three.uid, neuron_one.uid);
+	});
+}
+
+#[test]
+fn test_subscribe_reuses_vacant_uid_before_allocating() {
+	new_test_ext().execute_with(|| {
+		let neuron_one = subscribe_ok_neuron(1, 667);
+		let _neuron_two = subscribe_ok_neuron(2, 667);
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(1), 1));
+		assert_eq!(SubtensorModule::vacant_uids(), vec![neuron_one.uid]);
+
+		// The metagraph is not full, yet the vacant uid is reused instead of a fresh one.
+		let neuron_three = subscribe_ok_neuron(3, 667);
+		assert_eq!(neuron_three.uid, neuron_one.uid);
+		assert_eq!(SubtensorModule::get_neuron_count(), 2);
+		assert!(SubtensorModule::vacant_uids().is_empty());
+	});
+}
+
+#[test]
+fn test_subscribe_replace_does_not_leave_uid_vacant() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(1);
+		let neuron_one = subscribe_ok_neuron(1, 667);
+		run_to_block(11);
+
+		let neuron_two = subscribe_ok_neuron(2, 667);
+		assert_eq!(neuron_two.uid, neuron_one.uid);
+		assert!(SubtensorModule::vacant_uids().is_empty());
+	});
+}
+
+#[test]
+fn test_subscribe_replace_invalidates_weights_to_recycled_uid() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(2);
+		let coldkey_account_id = 667;
+		let neuron_one = subscribe_ok_neuron(1, coldkey_account_id);
+		let neuron_two = subscribe_ok_neuron(2, coldkey_account_id);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron_one.uid, 1_000_000_000);
+		assert_ok!(SubtensorModule::set_weights(<<Test as Trait>::Origin>::signed(1), vec![neuron_one.uid, neuron_two.uid], vec![u32::MAX / 2, u32::MAX / 2]));
+
+		run_to_block(11);
+		let neuron_three = subscribe_ok_neuron(3, coldkey_account_id);
+		assert_eq!(neuron_three.uid, neuron_two.uid);
+
+		// The edge to the replaced neuron is stale and pruned by the next sweep.
+		assert!(SubtensorModule::is_stale_edge(neuron_one.uid, neuron_three.uid));
+		run_to_block(12);
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_one), (vec![neuron_one.uid], vec![u32::MAX]));
+
+		// Emission from neuron one does not reach the newcomer.
+		SubtensorModule::emit_for_neuron(&neuron_one);
+		assert_eq!(SubtensorModule::get_stake_of_neuron_hotkey_account_by_uid(neuron_three.uid), 0);
+	});
+}
+
+#[test]
+fn test_subscribe_replace_returns_stake_to_coldkey() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(1);
+		let neuron_one = subscribe_ok_confirmed_neuron(1, 667);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron_one.uid, 100);
+
+		// Without weights the pending emission is not distributed on removal.
+		assert_ok!(SubtensorModule::set_weights(<<Test as Trait>::Origin>::signed(1), vec![], vec![]));
+		run_to_block(11);
+		let _neuron_two = subscribe_ok_confirmed_neuron(2, 668);
+
+		assert_eq!(SubtensorModule::get_coldkey_balance(&667), 100);
+	});
+}
+
+/********************************************
+	subscribing::swap_hotkey() tests
+*********************************************/
+#[test]
+fn test_swap_hotkey_dispatch_info_ok() {
+	new_test_ext().execute_with(|| {
+        let call = Call::SubtensorModule(SubtensorCall::swap_hotkey(1, 2));
+
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: 0,
+			class: DispatchClass::Normal,
+			pays_fee: Pays::Yes
+		});
+	});
+}
+
+#[test]
+fn test_swap_hotkey_ok() {
+	new_test_ext().execute_with(|| {
+		let old_hotkey = 1;
+		let new_hotkey = 2;
+		let coldkey_account_id = 667;
+		let stake = 10_000;
+
+		let neuron = subscribe_ok_confirmed_neuron(old_hotkey, coldkey_account_id);
+		let other = subscribe_ok_confirmed_neuron(3, coldkey_account_id);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron.uid, stake);
+		assert_ok!(SubtensorModule::set_weights(<<Test as Trait>::Origin>::signed(old_hotkey), vec![other.uid], vec![u32::MAX]));
+		SubtensorModule::update_pending_emission_for_neuron(neuron.uid, 500);
+
+		assert_ok!(SubtensorModule::swap_hotkey(<<Test as Trait>::Origin>::signed(coldkey_account_id), old_hotkey, new_hotkey));
+
+		// The new hotkey controls the same uid.
+		assert_eq!(SubtensorModule::is_hotkey_active(&old_hotkey), false);
+		assert_eq!(SubtensorModule::is_hotkey_active(&new_hotkey), true);
+		assert_eq!(SubtensorModule::get_uid_for_hotkey(&new_hotkey), neuron.uid);
+
+		let swapped = SubtensorModule::get_neuron_for_hotkey(&new_hotkey);
+		assert_eq!(swapped.uid, neuron.uid);
+		assert_eq!(swapped.hotkey, new_hotkey);
+		assert_eq!(swapped.coldkey, coldkey_account_id);
+		assert_eq!(swapped.ip, neuron.ip);
+
+		// Stake, weights and pending emission are preserved.
+		assert_eq!(SubtensorModule::get_stake_of_neuron_hotkey_account_by_uid(neuron.uid), stake);
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&swapped), (vec![other.uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::get_pending_emission_for_neuron(neuron.uid), 500);
+
+		// The new hotkey can act on behalf of the neuron.
+		assert_ok!(SubtensorModule::set_weights(<<Test as Trait>::Origin>::signed(new_hotkey), vec![neuron.uid], vec![u32::MAX]));
+		let result = SubtensorModule::set_weights(<<Test as Trait>::Origin>::signed(old_hotkey), vec![neuron.uid], vec![u32::MAX]);
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+#[test]
+fn test_swap_hotkey_err_new_hotkey_already_active() {
+	new_test_ext().execute_with(|| {
+		let coldkey_account_id = 667;
+		let _neuron_one = subscribe_ok_confirmed_neuron(1, coldkey_account_id);
+		let _neuron_two = subscribe_ok_confirmed_neuron(2, coldkey_account_id);
+
+		let result = SubtensorModule::swap_hotkey(<<Test as Trait>::Origin>::signed(coldkey_account_id), 1, 2);
+		assert_eq!(result, Err(Error::<Test>::AlreadyActive.into()));
+		assert_eq!(SubtensorModule::get_uid_for_hotkey(&1), 0);
+		assert_eq!(SubtensorModule::get_uid_for_hotkey(&2), 1);
+	});
+}
+
+#[test]
+fn test_swap_hotkey_err_non_associated_coldkey() {
+	new_test_ext().execute_with(|| {
+		let _neuron = subscribe_ok_confirmed_neuron(1, 667);
+
+		let result = SubtensorModule::swap_hotkey(<<Test as Trait>::Origin>::signed(668), 1, 2);
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+		assert_eq!(SubtensorModule::is_hotkey_active(&1), true);
+	});
+}
+
+#[test]
+fn test_swap_hotkey_err_not_active() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::swap_hotkey(<<Test as Trait>::Origin>::signed(667), 1, 2);
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+
+/********************************************
+	subscribing::accept_hotkey() tests
+*********************************************/
+#[test]
+fn test_accept_hotkey_dispatch_info_ok() {
+	new_test_ext().execute_with(|| {
+        let call = Call::SubtensorModule(SubtensorCall::accept_hotkey(1));
+
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: 0,
+			class: DispatchClass::Normal,
+			pays_fee: Pays::Yes
+		});
+	});
+}
+
+#[test]
+fn test_accept_hotkey_ok() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let coldkey_account_id = 667;
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,8), 66, 4, 0, coldkey_account_id));
+		assert_eq!(SubtensorModule::get_neuron_for_hotkey(&hotkey_account_id).coldkey_confirmed, false);
+
+		assert_ok!(SubtensorModule::accept_hotkey(<<Test as Trait>::Origin>::signed(coldkey_account_id), hotkey_account_id));
+		assert_eq!(SubtensorModule::get_neuron_for_hotkey(&hotkey_account_id).coldkey_confirmed, true);
+
+		// Resubscription keeps the association confirmed.
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(8,8,8,9), 66, 4, 0, coldkey_account_id));
+		assert_eq!(SubtensorModule::get_neuron_for_hotkey(&hotkey_account_id).coldkey_confirmed, true);
+	});
+}
+
+#[test]
+fn test_subscribe_with_own_hotkey_as_coldkey_is_confirmed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,8), 66, 4, 0, 1));
+		assert_eq!(SubtensorModule::get_neuron_for_hotkey(&1).coldkey_confirmed, true);
+	});
+}
+
+#[test]
+fn test_accept_hotkey_err_already_confirmed() {
+	new_test_ext().execute_with(|| {
+		let _neuron = subscribe_ok_confirmed_neuron(1, 667);
+
+		let result = SubtensorModule::accept_hotkey(<<Test as Trait>::Origin>::signed(667), 1);
+		assert_eq!(result, Err(Error::<Test>::ColdkeyAlreadyConfirmed.into()));
+	});
+}
+
+#[test]
+fn test_accept_hotkey_err_non_associated_coldkey() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,8), 66, 4, 0, 667));
+
+		let result = SubtensorModule::accept_hotkey(<<Test as Trait>::Origin>::signed(668), 1);
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+		assert_eq!(SubtensorModule::get_neuron_for_hotkey(&1).coldkey_confirmed, false);
+	});
+}
+
+#[test]
+fn test_accept_hotkey_err_not_active() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::accept_hotkey(<<Test as Trait>::Origin>::signed(667), 1);
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+#[test]
+fn test_unconfirmed_coldkey_can_not_swap_or_unsubscribe() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,8), 66, 4, 0, 667));
+
+		let result = SubtensorModule::swap_hotkey(<<Test as Trait>::Origin>::signed(667), 1, 2);
+		assert_eq!(result, Err(Error::<Test>::ColdkeyNotConfirmed.into()));
+
+		let result = SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(667), 1);
+		assert_eq!(result, Err(Error::<Test>::ColdkeyNotConfirmed.into()));
+
+		// The hotkey can still remove itself.
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(1), 1));
+	});
+}
+
+
+/********************************************
+	subscribing::set_ip_range_override() tests
+*********************************************/
+#[test]
+fn test_ip_range_override_allows_loopback() {
+	new_test_ext().execute_with(|| {
+		let hotkey_account_id = 1;
+		let coldkey_account_id = 667;
+
+		assert_ok!(SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(127,0,0,0), 8, true));
+		assert_eq!(SubtensorModule::ip_range_override((4, ipv4(127,0,0,0), 8)), Some(true));
+
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(hotkey_account_id), ipv4(127,0,0,1), 1337, 4, 0, coldkey_account_id));
+		assert_eq!(SubtensorModule::get_neuron_for_hotkey(&hotkey_account_id).ip, ipv4(127,0,0,1));
+
+		// The override only covers ipv4 loopback.
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv6(0,0,0,0,0,0,0,1), 1337, 6, 0, coldkey_account_id);
+		assert_eq!(result, Err(Error::<Test>::LoopbackIpAddress.into()));
+	});
+}
+
+#[test]
+fn test_ip_range_override_deny_public_range() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(8,8,8,0), 24, false));
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,8), 1337, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::IpAddressDenied.into()));
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,9,8), 1337, 4, 0, 667));
+	});
+}
+
+#[test]
+fn test_ip_range_override_deny_takes_precedence() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(10,0,0,0), 8, true));
+		assert_ok!(SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(10,1,0,0), 16, false));
+
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(10,0,0,1), 1337, 4, 0, 667));
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(10,1,0,1), 1337, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::IpAddressDenied.into()));
+	});
+}
+
+#[test]
+fn test_ip_range_override_does_not_allow_invalid_address() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 6, 0, 0, true));
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv6(0,0,0,0,0,0,0,0), 1337, 6, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::InvalidIpAddress.into()));
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv6(0,0,0,0,0,0,0,1), 1337, 6, 0, 667));
+	});
+}
+
+#[test]
+fn test_remove_ip_range_override() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(127,0,0,0), 8, true));
+		assert_ok!(SubtensorModule::remove_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(127,0,0,0), 8));
+		assert_eq!(SubtensorModule::ip_range_override((4, ipv4(127,0,0,0), 8)), None);
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(127,0,0,1), 1337, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::LoopbackIpAddress.into()));
+	});
+}
+
+#[test]
+fn test_set_ip_range_override_invalid_range() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(127,0,0,0), 33, true);
+		assert_eq!(result, Err(Error::<Test>::InvalidIpRange.into()));
+
+		let result = SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 5, 0, 8, true);
+		assert_eq!(result, Err(Error::<Test>::InvalidIpRange.into()));
+	});
+}
+
+#[test]
+fn test_set_ip_range_override_err_too_many_overrides() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(10,0,0,0), 8, true));
+		assert_ok!(SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(10,1,0,0), 16, false));
+		assert_eq!(SubtensorModule::ip_range_override_count(), 2);
+
+		let result = SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(127,0,0,0), 8, true);
+		assert_eq!(result, Err(Error::<Test>::TooManyIpRangeOverrides.into()));
+
+		// --- Changing an existing override does not count, removing one frees a slot.
+		assert_ok!(SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(10,1,0,0), 16, true));
+		assert_ok!(SubtensorModule::remove_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(10,0,0,0), 8));
+		assert_ok!(SubtensorModule::remove_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(10,0,0,0), 8));
+		assert_eq!(SubtensorModule::ip_range_override_count(), 1);
+		assert_ok!(SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::root(), 4, ipv4(127,0,0,0), 8, true));
+	});
+}
+
+#[test]
+fn test_set_ip_range_override_not_root() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::set_ip_range_override(<<Test as Trait>::Origin>::signed(1), 4, ipv4(127,0,0,0), 8, true);
+		assert_eq!(result, Err(DispatchError::BadOrigin.into()));
+
+		let result = SubtensorModule::remove_ip_range_override(<<Test as Trait>::Origin>::signed(1), 4, ipv4(127,0,0,0), 8);
+		assert_eq!(result, Err(DispatchError::BadOrigin.into()));
+	});
+}
+
+
+/********************************************
+	subscribing modality registry tests
+*********************************************/
+#[test]
+fn test_add_modality_ok() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(SubtensorModule::modality(0), Some(b"TEXT".to_vec()));
+		assert_eq!(SubtensorModule::modality(1), None);
+
+		assert_ok!(SubtensorModule::add_modality(<<Test as Trait>::Origin>::root(), 1, b"IMAGE".to_vec()));
+		assert_eq!(SubtensorModule::modality(1), Some(b"IMAGE".to_vec()));
+
+		let neuron = subscribe_neuron(1, ipv4(8,8,8,8), 66, 4, 1, 667);
+		assert_eq!(neuron.modality, 1);
+		assert_eq!(SubtensorModule::get_uids_for_modality(1), vec![neuron.uid]);
+		assert_eq!(SubtensorModule::get_uids_for_modality(0), Vec::<u64>::new());
+	});
+}
+
+#[test]
+fn test_add_modality_already_exists() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::add_modality(<<Test as Trait>::Origin>::root(), 0, b"TENSOR".to_vec());
+		assert_eq!(result, Err(Error::<Test>::ModalityAlreadyExists.into()));
+		assert_eq!(SubtensorModule::modality(0), Some(b"TEXT".to_vec()));
+	});
+}
+
+#[test]
+fn test_retire_modality_rejects_new_subscriptions() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::add_modality(<<Test as Trait>::Origin>::root(), 1, b"IMAGE".to_vec()));
+		let neuron = subscribe_neuron(1, ipv4(8,8,8,8), 66, 4, 1, 667);
+
+		assert_ok!(SubtensorModule::retire_modality(<<Test as Trait>::Origin>::root(), 1));
+		assert_eq!(SubtensorModule::modality(1), None);
+
+		// A new neuron can not subscribe with the retired modality.
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,8), 66, 4, 1, 667);
+		assert_eq!(result, Err(Error::<Test>::InvalidModality.into()));
+
+		// The existing neuron keeps its modality on resubscription.
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,9), 66, 4, 1, 667));
+		assert_eq!(SubtensorModule::get_neuron_for_hotkey(&1).modality, 1);
+		assert_eq!(SubtensorModule::get_uids_for_modality(1), vec![neuron.uid]);
+	});
+}
+
+#[test]
+fn test_retire_modality_not_registered() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::retire_modality(<<Test as Trait>::Origin>::root(), 7);
+		assert_eq!(result, Err(Error::<Test>::InvalidModality.into()));
+	});
+}
+
+#[test]
+fn test_modality_registry_not_root() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::add_modality(<<Test as Trait>::Origin>::signed(1), 1, b"IMAGE".to_vec());
+		assert_eq!(result, Err(DispatchError::BadOrigin.into()));
+
+		let result = SubtensorModule::retire_modality(<<Test as Trait>::Origin>::signed(1), 0);
+		assert_eq!(result, Err(DispatchError::BadOrigin.into()));
+	});
+}
+
+#[test]
+fn test_resubscribe_changes_modality() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::add_modality(<<Test as Trait>::Origin>::root(), 2, b"TENSOR".to_vec()));
+		let neuron = subscribe_ok_neuron(1, 667);
+		assert_eq!(SubtensorModule::get_uids_for_modality(0), vec![neuron.uid]);
+
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,8), 66, 4, 2, 667));
+		assert_eq!(SubtensorModule::get_neuron_for_hotkey(&1).modality, 2);
+		assert_eq!(SubtensorModule::get_uids_for_modality(0), Vec::<u64>::new());
+		assert_eq!(SubtensorModule::get_uids_for_modality(2), vec![neuron.uid]);
+	});
+}
+
+#[test]
+fn test_unsubscribe_removes_uid_from_modality_index() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(1, 667);
+		let neuron_b = subscribe_ok_neuron(2, 667);
+		let mut uids = SubtensorModule::get_uids_for_modality(0);
+		uids.sort();
+		assert_eq!(uids, vec![neuron_a.uid, neuron_b.uid]);
+
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(1), 1));
+		assert_eq!(SubtensorModule::get_uids_for_modality(0), vec![neuron_b.uid]);
+	});
+}
+
+/********************************************
+	subscribing::ensure_endpoint_available() tests
+*********************************************/
+#[test]
+fn test_subscribe_err_endpoint_in_use() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,8), 1337, 4, 0, 667));
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,8), 1337, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::EndpointInUse.into()));
+		assert_eq!(SubtensorModule::is_hotkey_active(&2), false);
+
+		// --- Another port on the same ip is a different endpoint.
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,8), 1338, 4, 0, 667));
+	});
+}
+
+#[test]
+fn test_subscribe_err_endpoint_in_use_does_not_take_uid() {
+	new_test_ext().execute_with(|| {
+		let neuron_one = subscribe_ok_neuron(1, 667);
+		let neuron_two = subscribe_ok_neuron(2, 667);
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(3), neuron_one.ip, neuron_one.port, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::EndpointInUse.into()));
+		assert_eq!(SubtensorModule::get_neuron_count(), 2);
+
+		// --- A vacant uid is kept when the subscription fails.
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(2), 2));
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(3), neuron_one.ip, neuron_one.port, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::EndpointInUse.into()));
+		assert_eq!(SubtensorModule::get_neuron_count(), 2);
+		assert_eq!(SubtensorModule::vacant_uids(), vec![neuron_two.uid]);
+	});
+}
+
+#[test]
+fn test_update_err_endpoint_in_use() {
+	new_test_ext().execute_with(|| {
+		let neuron_one = subscribe_ok_neuron(1, 667);
+		let _neuron_two = subscribe_ok_neuron(2, 667);
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), neuron_one.ip, neuron_one.port, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::EndpointInUse.into()));
+
+		// --- A neuron can resubscribe with its own endpoint.
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), neuron_one.ip, neuron_one.port, 4, 0, 667));
+	});
+}
+
+#[test]
+fn test_endpoint_released_on_update_and_unsubscribe() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,8), 1337, 4, 0, 667));
+		assert_eq!(SubtensorModule::endpoint_uid((4, ipv4(8,8,8,8), 1337)), Some(0));
+
+		// --- Moving the neuron releases its old endpoint.
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,9), 1337, 4, 0, 667));
+		assert_eq!(SubtensorModule::endpoint_uid((4, ipv4(8,8,8,8), 1337)), None);
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,8), 1337, 4, 0, 667));
+
+		// --- Unsubscribing releases the endpoint.
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(1), 1));
+		assert_eq!(SubtensorModule::endpoint_uid((4, ipv4(8,8,8,9), 1337)), None);
+		assert_eq!(SubtensorModule::uids_per_ip((4, ipv4(8,8,8,9))), 0);
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(3), ipv4(8,8,8,9), 1337, 4, 0, 667));
+	});
+}
+
+#[test]
+fn test_subscribe_takes_over_endpoint_of_replaced_neuron() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(1);
+		let neuron_one = subscribe_ok_neuron(1, 667);
+		run_to_block(11);
+
+		// The new neuron replaces the neuron serving the endpoint, so the endpoint is free.
+		let neuron_two = subscribe_neuron(2, neuron_one.ip, neuron_one.port, 4, 0, 667);
+		assert_eq!(neuron_two.uid, neuron_one.uid);
+		assert_eq!(SubtensorModule::is_hotkey_active(&1), false);
+		assert_eq!(SubtensorModule::endpoint_uid((4, neuron_one.ip, neuron_one.port)), Some(neuron_two.uid));
+		assert_eq!(SubtensorModule::uids_per_ip((4, neuron_one.ip)), 1);
+	});
+}
+
+#[test]
+fn test_endpoint_in_use_does_not_replace_neuron() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(2);
+		let neuron_one = subscribe_ok_neuron(1, 667);
+		let _neuron_two = subscribe_ok_neuron(2, 667);
+		SubtensorModule::add_stake_to_neuron_hotkey_account(neuron_one.uid, 10_000);
+		run_to_block(11);
+
+		// The endpoint of the neuron which is not replaced is still in use.
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(3), neuron_one.ip, neuron_one.port, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::EndpointInUse.into()));
+		assert_eq!(SubtensorModule::is_hotkey_active(&2), true);
+	});
+}
+
+#[test]
+fn test_additional_endpoints_are_indexed() {
+	new_test_ext().execute_with(|| {
+		let neuron_one = subscribe_ok_neuron(1, 667);
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,4,4), 9090, 4, EndpointProtocol::Http, 1));
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,4,4), 9090, 4, EndpointProtocol::Prometheus, 1));
+		assert_eq!(SubtensorModule::endpoint_uid((4, ipv4(8,8,4,4), 9090)), Some(neuron_one.uid));
+
+		// --- Another neuron can neither subscribe nor add an endpoint on the address.
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,4,4), 9090, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::EndpointInUse.into()));
+		let _neuron_two = subscribe_ok_neuron(2, 667);
+		let result = SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,4,4), 9090, 4, EndpointProtocol::Http, 1);
+		assert_eq!(result, Err(Error::<Test>::EndpointInUse.into()));
+
+		// --- The address stays claimed until the last endpoint serving it is removed.
+		assert_ok!(SubtensorModule::remove_endpoint(<<Test as Trait>::Origin>::signed(1), 1));
+		assert_eq!(SubtensorModule::endpoint_uid((4, ipv4(8,8,4,4), 9090)), Some(neuron_one.uid));
+		assert_ok!(SubtensorModule::remove_endpoint(<<Test as Trait>::Origin>::signed(1), 1));
+		assert_eq!(SubtensorModule::endpoint_uid((4, ipv4(8,8,4,4), 9090)), None);
+
+		// --- Unsubscribing releases the additional endpoints.
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,4,4), 9091, 4, EndpointProtocol::Http, 1));
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(1), 1));
+		assert_eq!(SubtensorModule::endpoint_uid((4, ipv4(8,8,4,4), 9091)), None);
+		assert_ok!(SubtensorModule::add_endpoint(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,4,4), 9091, 4, EndpointProtocol::Http, 1));
+	});
+}
+
+#[test]
+fn test_max_uids_per_ip() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::set_max_uids_per_ip(<<Test as Trait>::Origin>::root(), 2));
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,8), 1337, 4, 0, 667));
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,8), 1338, 4, 0, 667));
+		assert_eq!(SubtensorModule::uids_per_ip((4, ipv4(8,8,8,8))), 2);
+
+		let result = SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(3), ipv4(8,8,8,8), 1339, 4, 0, 667);
+		assert_eq!(result, Err(Error::<Test>::TooManyUidsPerIp.into()));
+
+		// --- Neurons already on the ip can change their port.
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,8), 1340, 4, 0, 667));
+
+		// --- A neuron moving away frees a slot on the ip.
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(2), ipv4(8,8,8,9), 1340, 4, 0, 667));
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(3), ipv4(8,8,8,8), 1339, 4, 0, 667));
+
+		// --- 0 removes the limit.
+		assert_ok!(SubtensorModule::set_max_uids_per_ip(<<Test as Trait>::Origin>::root(), 0));
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(4), ipv4(8,8,8,8), 1341, 4, 0, 667));
+	});
+}
+
+#[test]
+fn test_set_max_uids_per_ip_not_root() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::set_max_uids_per_ip(<<Test as Trait>::Origin>::signed(1), 1);
+		assert_eq!(result, Err(DispatchError::BadOrigin.into()));
+	});
+}
@@ -0,0 +1,124 @@
+use pallet_subtensor::{Error, TransportKey};
+use frame_support::{assert_ok};
+use frame_system::Trait;
+mod mock;
+use mock::*;
+use sp_core::{ed25519, Pair};
+use pallet_subtensor::{Call as SubtensorCall};
+use frame_support::dispatch::{GetDispatchInfo, DispatchInfo};
+use frame_support::weights::{DispatchClass, Pays};
+
+/********************************************
+	transport::do_set_transport_key() tests
+*********************************************/
+#[test]
+fn fee_and_weight_of_set_transport_key_is_zero() {
+	new_test_ext().execute_with(|| {
+		let call = SubtensorCall::set_transport_key(Some(TransportKey::X25519([1; 32])));
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: 0,
+			class: DispatchClass::Normal,
+			pays_fee: Pays::No
+		});
+	});
+}
+
+#[test]
+fn test_set_transport_key_ok() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(1, 667);
+		assert_eq!(neuron.transport_key, None);
+
+		assert_ok!(SubtensorModule::set_transport_key(<<Test as Trait>::Origin>::signed(1), Some(TransportKey::X25519([7; 32]))));
+		assert_eq!(SubtensorModule::get_transport_key_for_neuron(neuron.uid), Some(TransportKey::X25519([7; 32])));
+
+		// --- Resubscribing keeps the key.
+		assert_ok!(SubtensorModule::subscribe(<<Test as Trait>::Origin>::signed(1), ipv4(8,8,8,9), 66, 4, 0, 667));
+		assert_eq!(SubtensorModule::get_transport_key_for_neuron(neuron.uid), Some(TransportKey::X25519([7; 32])));
+
+		assert_ok!(SubtensorModule::set_transport_key(<<Test as Trait>::Origin>::signed(1), None));
+		assert_eq!(SubtensorModule::get_transport_key_for_neuron(neuron.uid), None);
+	});
+}
+
+#[test]
+fn test_set_transport_key_err_invalid_key() {
+	new_test_ext().execute_with(|| {
+		let _neuron = subscribe_ok_neuron(1, 667);
+		let result = SubtensorModule::set_transport_key(<<Test as Trait>::Origin>::signed(1), Some(TransportKey::Ed25519([0; 32])));
+		assert_eq!(result, Err(Error::<Test>::InvalidTransportKey.into()));
+	});
+}
+
+#[test]
+fn test_set_transport_key_respects_update_cooldown() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(SubtensorModule::set_subscription_rate_limits(<<Test as Trait>::Origin>::root(), None, None, Some(5)));
+		let neuron = subscribe_ok_neuron(1, 667);
+
+		let result = SubtensorModule::set_transport_key(<<Test as Trait>::Origin>::signed(1), Some(TransportKey::X25519([7; 32])));
+		assert_eq!(result, Err(Error::<Test>::EndpointUpdateTooFast.into()));
+		assert_eq!(SubtensorModule::get_transport_key_for_neuron(neuron.uid), None);
+
+		run_to_block(5);
+		assert_ok!(SubtensorModule::set_transport_key(<<Test as Trait>::Origin>::signed(1), Some(TransportKey::X25519([7; 32]))));
+		assert_eq!(SubtensorModule::last_endpoint_update(neuron.uid), 5);
+
+		let result = SubtensorModule::set_transport_key(<<Test as Trait>::Origin>::signed(1), None);
+		assert_eq!(result, Err(Error::<Test>::EndpointUpdateTooFast.into()));
+	});
+}
+
+#[test]
+fn test_set_transport_key_err_not_active() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::set_transport_key(<<Test as Trait>::Origin>::signed(1), Some(TransportKey::X25519([7; 32])));
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+/********************************************
+	transport::verify_transport_signature() tests
+*********************************************/
+#[test]
+fn test_verify_transport_signature() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(1, 667);
+		let pair = ed25519::Pair::from_seed(&[3; 32]);
+		assert_ok!(SubtensorModule::set_transport_key(<<Test as Trait>::Origin>::signed(1), Some(TransportKey::Ed25519(pair.public().0))));
+
+		let challenge = b"challenge nonce";
+		let signature = pair.sign(challenge);
+		assert!(SubtensorModule::verify_transport_signature(neuron.uid, challenge, &signature.0));
+		assert!(!SubtensorModule::verify_transport_signature(neuron.uid, b"other challenge", &signature.0));
+
+		let other = ed25519::Pair::from_seed(&[4; 32]);
+		assert!(!SubtensorModule::verify_transport_signature(neuron.uid, challenge, &other.sign(challenge).0));
+	});
+}
+
+#[test]
+fn test_verify_transport_signature_without_ed25519_key() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(1, 667);
+		let pair = ed25519::Pair::from_seed(&[3; 32]);
+		let signature = pair.sign(b"challenge");
+		assert!(!SubtensorModule::verify_transport_signature(neuron.uid, b"challenge", &signature.0));
+
+		assert_ok!(SubtensorModule::set_transport_key(<<Test as Trait>::Origin>::signed(1), Some(TransportKey::X25519(pair.public().0))));
+		assert!(!SubtensorModule::verify_transport_signature(neuron.uid, b"challenge", &signature.0));
+	});
+}
+
+#[test]
+fn test_transport_key_matches() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(1, 667);
+		assert_ok!(SubtensorModule::set_transport_key(<<Test as Trait>::Origin>::signed(1), Some(TransportKey::X25519([7; 32]))));
+
+		assert!(SubtensorModule::transport_key_matches(neuron.uid, &TransportKey::X25519([7; 32])));
+		assert!(!SubtensorModule::transport_key_matches(neuron.uid, &TransportKey::X25519([8; 32])));
+		assert!(!SubtensorModule::transport_key_matches(neuron.uid, &TransportKey::Ed25519([7; 32])));
+		assert!(!SubtensorModule::transport_key_matches(99, &TransportKey::X25519([7; 32])));
+	});
+}
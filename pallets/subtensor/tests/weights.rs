@@ -3,9 +3,11 @@ use mock::*;
 use mock::{TestXt};
 use pallet_subtensor::{Call as SubtensorCall, Error};
 use frame_support::weights::{GetDispatchInfo, DispatchInfo, DispatchClass, Pays};
-use frame_support::{assert_ok};
+use frame_support::{assert_ok, traits::Get};
+use frame_system::Trait;
 use sp_runtime::DispatchError;
 use fixed::types::U64F64;
+use sp_arithmetic::{FixedU128, FixedPointNumber, traits::One};
 
 
 /***************************
@@ -22,7 +24,7 @@ fn test_set_weights_dispatch_info_ok() {
 		let call = Call::SubtensorModule(SubtensorCall::set_weights(w_uids, w_vals));
 
 		assert_eq!(call.get_dispatch_info(), DispatchInfo {
-			weight: 0,
+			weight: SubtensorModule::max_prune_stale_weights_weight(),
 			class: DispatchClass::Normal,
 			pays_fee: Pays::No
 		});
@@ -129,8 +131,8 @@ fn set_weights_ok_with_weights() {
 	new_test_ext().execute_with(|| {
 		let neurons = vec![
 			subscribe_neuron(55, 10, 666, 4, 0, 66),
-			subscribe_neuron(66, 10, 666, 4, 0, 66),
-			subscribe_neuron(77, 10, 666, 4, 0, 66)
+			subscribe_neuron(66, 10, 667, 4, 0, 66),
+			subscribe_neuron(77, 10, 668, 4, 0, 66)
 		];
 
 		let initial_stakes = vec![10000,0,0];
@@ -140,7 +142,7 @@ fn set_weights_ok_with_weights() {
 
 		// Expectations
 		let expect_weight_uids = vec![neurons[1].uid, neurons[2].uid];
-		let expect_weight_values = vec![u32::MAX / 2, u32::MAX / 2];
+		let expect_weight_values = vec![u32::MAX / 2 + 1, u32::MAX / 2]; // The odd unit goes to the first weight
 
 		// Dish out the stake for all neurons
 		for (i, neuron) in neurons.iter().enumerate() {
@@ -167,7 +169,7 @@ fn set_weights_ok_with_weights() {
 		assert_eq!(stakes[0], initial_stakes[0]); // Stake of sender should remain unchanged
 		assert!(stakes[1] >  initial_stakes[1]); // The stake of destination 1 should have increased
 		assert!(stakes[2] >  initial_stakes[2]); // The stake destination 2 should habe increased
-		assert_eq!(stakes[1], stakes[2]); // The stakes should have increased the same
+		assert!(stakes[1].max(stakes[2]) - stakes[1].min(stakes[2]) <= 1); // The stakes should have increased the same, up to the odd unit
 	});
 }
 
@@ -236,5 +238,535 @@ fn test_set_weights_err_invalid_uid() {
 	});
 }
 
+#[test]
+fn test_set_weights_err_too_many_weights() {
+	new_test_ext().execute_with(|| {
+		let _neuron = subscribe_ok_neuron(55, 66);
+		let max = MaxWeightsPerNeuron::get() as u64;
+		let weight_keys: Vec<AccountId> = (0..max + 1).collect();
+		let weight_values: Vec<u32> = vec![1; (max + 1) as usize];
+
+		let result = SubtensorModule::set_weights(Origin::signed(55), weight_keys, weight_values);
+
+		assert_eq!(result, Err(Error::<Test>::TooManyWeights.into()));
+	});
+}
+
+#[test]
+fn test_set_weights_stores_edges_sorted_by_uid() {
+	new_test_ext().execute_with(|| {
+		let neurons = vec![
+			subscribe_ok_neuron(55, 66),
+			subscribe_ok_neuron(66, 66),
+			subscribe_ok_neuron(77, 66)
+		];
+
+		let weight_keys = vec![neurons[2].uid, neurons[0].uid, neurons[1].uid];
+		let weight_values = vec![u32::MAX / 2, 0, u32::MAX / 2];
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), weight_keys, weight_values));
+
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neurons[0]),
+			(vec![neurons[0].uid, neurons[1].uid, neurons[2].uid], vec![0, u32::MAX / 2, u32::MAX / 2 + 1]));
+	});
+}
+
+#[test]
+fn test_get_weight_for_edge() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_b.uid], vec![u32::MAX]));
+
+		assert_eq!(SubtensorModule::get_weight_for_edge(neuron_a.uid, neuron_b.uid), u32::MAX);
+		assert_eq!(SubtensorModule::get_weight_for_edge(neuron_a.uid, neuron_a.uid), 0);
+		assert_eq!(SubtensorModule::get_weight_for_edge(neuron_b.uid, neuron_b.uid), u32::MAX); // Initial self weight.
+		assert_eq!(SubtensorModule::get_weight_for_edge(9999, neuron_a.uid), 0);
+	});
+}
+
+/***************************
+  weights rate limit tests
+*****************************/
+
+#[test]
+fn test_set_weights_versioned_dispatch_info_ok() {
+	new_test_ext().execute_with(|| {
+		let call = Call::SubtensorModule(SubtensorCall::set_weights_versioned(vec![1], vec![1], 0));
+
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: SubtensorModule::max_prune_stale_weights_weight(),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::No
+		});
+	});
+}
+
+#[test]
+fn test_set_weights_versioned_with_current_metagraph_version_ok() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		let version = SubtensorModule::metagraph_version();
+
+		assert_ok!(SubtensorModule::set_weights_versioned(Origin::signed(55), vec![neuron_b.uid], vec![u32::MAX], version));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_b.uid], vec![u32::MAX]));
+	});
+}
+
+#[test]
+fn test_set_weights_versioned_err_stale_metagraph() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		let version = SubtensorModule::metagraph_version();
+
+		// --- Both a removal and a registration bump the version the client computed against.
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(66), 66));
+		let result = SubtensorModule::set_weights_versioned(Origin::signed(55), vec![neuron_a.uid], vec![u32::MAX], version);
+		assert_eq!(result, Err(Error::<Test>::StaleMetagraph.into()));
+
+		let version = SubtensorModule::metagraph_version();
+		let _neuron_c = subscribe_ok_neuron(77, 66);
+		let result = SubtensorModule::set_weights_versioned(Origin::signed(55), vec![neuron_b.uid], vec![u32::MAX], version);
+		assert_eq!(result, Err(Error::<Test>::StaleMetagraph.into()));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_a.uid], vec![u32::MAX]));
+
+		// --- set_weights is not checked against the metagraph.
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid], vec![u32::MAX]));
+	});
+}
+
+#[test]
+fn test_set_weights_err_setting_weights_too_fast() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(55, 66);
+		assert_ok!(SubtensorModule::set_weights_rate_limit(Origin::root(), Some(5)));
+
+		// --- The first weights of a neuron are never rate limited.
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron.uid], vec![u32::MAX]));
+
+		run_to_block(4);
+		let result = SubtensorModule::set_weights(Origin::signed(55), vec![neuron.uid], vec![u32::MAX]);
+		assert_eq!(result, Err(Error::<Test>::SettingWeightsTooFast.into()));
+
+		run_to_block(5);
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron.uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::last_weights_set(neuron.uid), Some(5));
+	});
+}
+
+#[test]
+fn test_set_weights_rate_limit_override_and_reset() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(SubtensorModule::get_weights_set_rate_limit(), WeightsSetRateLimit::get());
+
+		assert_ok!(SubtensorModule::set_weights_rate_limit(Origin::root(), Some(10)));
+		assert_eq!(SubtensorModule::get_weights_set_rate_limit(), 10);
+
+		assert_ok!(SubtensorModule::set_weights_rate_limit(Origin::root(), None));
+		assert_eq!(SubtensorModule::get_weights_set_rate_limit(), WeightsSetRateLimit::get());
+	});
+}
+
+#[test]
+fn test_set_weights_rate_limit_requires_root() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::set_weights_rate_limit(Origin::signed(55), Some(10));
+		assert_eq!(result, Err(DispatchError::BadOrigin.into()));
+	});
+}
+
+#[test]
+fn test_unsubscribe_clears_last_weights_set() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(55, 66);
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron.uid], vec![u32::MAX]));
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(55), 55));
+		assert_eq!(SubtensorModule::last_weights_set(neuron.uid), None);
+	});
+}
+
+/***************************
+  weight limits tests
+*****************************/
+
+#[test]
+fn test_set_weights_err_self_weight_too_high() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		assert_ok!(SubtensorModule::set_weight_limits(Origin::root(), u32::MAX / 2 + 1, 0, u32::MAX));
+
+		let result = SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![3, 1]);
+		assert_eq!(result, Err(Error::<Test>::SelfWeightTooHigh.into()));
+
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![1, 1]));
+	});
+}
+
+#[test]
+fn test_set_weights_err_not_enough_non_self_weights() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		let neuron_c = subscribe_ok_neuron(77, 66);
+		assert_ok!(SubtensorModule::set_weight_limits(Origin::root(), u32::MAX, 2, u32::MAX));
+
+		let result = SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![1, 1]);
+		assert_eq!(result, Err(Error::<Test>::NotEnoughNonSelfWeights.into()));
+
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_b.uid, neuron_c.uid], vec![1, 1]));
+	});
+}
+
+#[test]
+fn test_set_weights_zero_weights_do_not_count_as_non_self_weights() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		let neuron_c = subscribe_ok_neuron(77, 66);
+		assert_ok!(SubtensorModule::set_weight_limits(Origin::root(), u32::MAX, 2, u32::MAX));
+
+		let result = SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid, neuron_c.uid], vec![1, 1, 0]);
+		assert_eq!(result, Err(Error::<Test>::NotEnoughNonSelfWeights.into()));
+	});
+}
+
+#[test]
+fn test_set_weights_err_edge_weight_too_high() {
+	new_test_ext().execute_with(|| {
+		let _neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		let neuron_c = subscribe_ok_neuron(77, 66);
+		assert_ok!(SubtensorModule::set_weight_limits(Origin::root(), u32::MAX, 0, u32::MAX / 2 + 1));
+
+		let result = SubtensorModule::set_weights(Origin::signed(55), vec![neuron_b.uid, neuron_c.uid], vec![2, 1]);
+		assert_eq!(result, Err(Error::<Test>::EdgeWeightTooHigh.into()));
+
+		// --- The limit applies to the normalized weights.
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_b.uid, neuron_c.uid], vec![u32::MAX, u32::MAX]));
+	});
+}
+
+#[test]
+fn test_set_weight_limits_requires_root() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::set_weight_limits(Origin::signed(55), 0, 0, 0);
+		assert_eq!(result, Err(DispatchError::BadOrigin.into()));
+		assert_eq!(SubtensorModule::max_self_weight(), u32::MAX);
+		assert_eq!(SubtensorModule::min_non_self_weights(), 0);
+		assert_eq!(SubtensorModule::max_edge_weight(), u32::MAX);
+	});
+}
+
+/***************************
+  pub fn update_weights() tests
+*****************************/
+
+#[test]
+fn test_update_weights_dispatch_info_ok() {
+	new_test_ext().execute_with(|| {
+		let call = Call::SubtensorModule(SubtensorCall::update_weights(vec![(1, 1)], None));
+
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: SubtensorModule::max_prune_stale_weights_weight(),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::No
+		});
+	});
+}
+
+#[test]
+fn test_update_weights_inserts_changes_and_removes_edges() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		let neuron_c = subscribe_ok_neuron(77, 66);
+
+		// --- Insert an edge next to the initial self weight.
+		assert_ok!(SubtensorModule::update_weights(Origin::signed(55), vec![(neuron_b.uid, u32::MAX)], None));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a),
+			(vec![neuron_a.uid, neuron_b.uid], vec![u32::MAX / 2 + 1, u32::MAX / 2]));
+
+		// --- Remove the self weight and add another edge.
+		assert_ok!(SubtensorModule::update_weights(Origin::signed(55), vec![(neuron_c.uid, u32::MAX / 2), (neuron_a.uid, 0)], None));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a),
+			(vec![neuron_b.uid, neuron_c.uid], vec![u32::MAX / 2 + 1, u32::MAX / 2]));
+
+		// --- Change an edge.
+		assert_ok!(SubtensorModule::update_weights(Origin::signed(55), vec![(neuron_c.uid, 0), (neuron_b.uid, 1)], None));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_b.uid], vec![u32::MAX]));
+	});
+}
+
+#[test]
+fn test_update_weights_err_duplicate_uids() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_neuron(55, 66);
+		let result = SubtensorModule::update_weights(Origin::signed(55), vec![(neuron.uid, 1), (neuron.uid, 0)], None);
+		assert_eq!(result, Err(Error::<Test>::DuplicateUids.into()));
+	});
+}
 
+#[test]
+fn test_update_weights_err_too_many_weights() {
+	new_test_ext().execute_with(|| {
+		let _neuron = subscribe_ok_neuron(55, 66);
+		let max = MaxWeightsPerNeuron::get() as u64;
+		let changes: Vec<(u64, u32)> = (0..max + 1).map(|uid| (uid, 0)).collect();
+
+		let result = SubtensorModule::update_weights(Origin::signed(55), changes, None);
+		assert_eq!(result, Err(Error::<Test>::TooManyWeights.into()));
+	});
+}
+
+#[test]
+fn test_update_weights_err_invalid_uid() {
+	new_test_ext().execute_with(|| {
+		let _neuron = subscribe_ok_neuron(55, 66);
+		let result = SubtensorModule::update_weights(Origin::signed(55), vec![(9999, 1)], None);
+		assert_eq!(result, Err(Error::<Test>::InvalidUid.into()));
+
+		// --- Removing an edge which does not exist is a no-op.
+		assert_ok!(SubtensorModule::update_weights(Origin::signed(55), vec![(9999, 0)], None));
+	});
+}
+
+#[test]
+fn test_update_weights_err_stale_metagraph() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let version = SubtensorModule::metagraph_version();
+		let neuron_b = subscribe_ok_neuron(66, 66);
+
+		let result = SubtensorModule::update_weights(Origin::signed(55), vec![(neuron_b.uid, u32::MAX)], Some(version));
+		assert_eq!(result, Err(Error::<Test>::StaleMetagraph.into()));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_a.uid], vec![u32::MAX]));
+
+		let version = SubtensorModule::metagraph_version();
+		assert_ok!(SubtensorModule::update_weights(Origin::signed(55), vec![(neuron_b.uid, u32::MAX)], Some(version)));
+	});
+}
 
+#[test]
+fn test_update_weights_err_not_active() {
+	new_test_ext().execute_with(|| {
+		let result = SubtensorModule::update_weights(Origin::signed(55), vec![(0, 1)], None);
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+/***************************
+  stale weights pruning tests
+*****************************/
+
+#[test]
+fn test_unsubscribe_makes_edges_stale() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![1, 1]));
+		assert!(!SubtensorModule::is_stale_edge(neuron_a.uid, neuron_b.uid));
+
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(66), 66));
+		assert!(SubtensorModule::is_stale_edge(neuron_a.uid, neuron_b.uid));
+
+		// --- The sweep removes the edge and re-normalizes the remaining weights.
+		run_to_block(1);
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_a.uid], vec![u32::MAX]));
+	});
+}
+
+#[test]
+fn test_edges_set_after_recycling_are_not_stale() {
+	new_test_ext().execute_with(|| {
+		MaxNeurons::set(2);
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(66), 66));
+		let neuron_c = subscribe_ok_neuron(77, 66);
+		assert_eq!(neuron_c.uid, neuron_b.uid);
+
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_c.uid], vec![u32::MAX]));
+		assert!(!SubtensorModule::is_stale_edge(neuron_a.uid, neuron_c.uid));
+
+		run_to_block(1);
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_c.uid], vec![u32::MAX]));
+	});
+}
+
+#[test]
+fn test_sweep_stale_weights_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let neurons: Vec<_> = (0..5).map(|hotkey| subscribe_ok_neuron(hotkey, 66)).collect();
+		for neuron in neurons.iter().take(4) {
+			assert_ok!(SubtensorModule::set_weights(Origin::signed(neuron.hotkey), vec![neuron.uid, neurons[4].uid], vec![1, 1]));
+		}
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(4), 4));
+
+		// --- MaxWeightPrunesPerBlock rows are checked each block.
+		run_to_block(1);
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neurons[0]), (vec![neurons[0].uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neurons[1]), (vec![neurons[1].uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neurons[2]).0, vec![neurons[2].uid, neurons[4].uid]);
+
+		run_to_block(3);
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neurons[2]), (vec![neurons[2].uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neurons[3]), (vec![neurons[3].uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::sweep_stale_weights(), 0);
+	});
+}
+
+#[test]
+fn test_sweep_stale_weights_weighs_visited_edges() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![1, 1]));
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(66), 66));
+
+		// --- Both rows are checked, only the row of neuron_a is active and holds two edges.
+		let db_weight = <Test as Trait>::DbWeight::get();
+		let expected = db_weight.reads_writes(3, 2) + db_weight.reads(1) + db_weight.reads(2)
+			+ SubtensorModule::prune_stale_weights_weight(2) + db_weight.reads(1);
+		assert_eq!(SubtensorModule::sweep_stale_weights(), expected);
+	});
+}
+
+#[test]
+fn test_emit_prunes_stale_edges_before_sweep() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![1, 1]));
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(66), 66));
+
+		SubtensorModule::emit_for_neuron(&neuron_a);
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_a.uid], vec![u32::MAX]));
+	});
+}
+
+#[test]
+fn test_update_weights_drops_stale_edges() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		let neuron_c = subscribe_ok_neuron(77, 66);
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![1, 1]));
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(66), 66));
+
+		assert_ok!(SubtensorModule::update_weights(Origin::signed(55), vec![(neuron_c.uid, u32::MAX)], None));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a),
+			(vec![neuron_a.uid, neuron_c.uid], vec![u32::MAX / 2 + 1, u32::MAX / 2]));
+	});
+}
+
+#[test]
+fn test_update_weights_rejected_does_not_prune() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![1, 1]));
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(66), 66));
+		let weights = SubtensorModule::weights(neuron_a.uid);
+
+		// --- The stale edge is only pruned once the update is accepted.
+		let result = SubtensorModule::update_weights(Origin::signed(55), vec![(9999, 1)], None);
+		assert_eq!(result, Err(Error::<Test>::InvalidUid.into()));
+		assert_eq!(SubtensorModule::weights(neuron_a.uid), weights);
+	});
+}
+
+#[test]
+fn test_set_weights_err_weights_sum_to_zero() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+
+		let result = SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![0, 0]);
+		assert_eq!(result, Err(Error::<Test>::WeightsSumToZero.into()));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_a.uid], vec![u32::MAX]));
+	});
+}
+
+#[test]
+fn test_set_weights_sum_to_max() {
+	new_test_ext().execute_with(|| {
+		let neurons: Vec<_> = (0..7).map(|hotkey| subscribe_ok_neuron(hotkey, 66)).collect();
+		let uids: Vec<u64> = neurons.iter().map(|neuron| neuron.uid).collect();
+
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(0), uids, vec![3, 5, 7, 11, 13, 17, 19]));
+		let (_, values) = SubtensorModule::get_weights_for_neuron(&neurons[0]);
+		assert_eq!(values.iter().map(|x| *x as u64).sum::<u64>(), u32::MAX as u64);
+	});
+}
+
+/********************************************
+	get_weight_infos() / get_weight_matrix() tests
+*********************************************/
+#[test]
+fn test_get_weight_infos_ok() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![1, 3]));
+
+		let infos = SubtensorModule::get_weight_infos(neuron_a.uid);
+		let (uids, values) = SubtensorModule::get_weights_for_neuron(&neuron_a);
+		assert_eq!(infos.iter().map(|info| info.uid).collect::<Vec<u64>>(), uids);
+		assert_eq!(infos.iter().map(|info| info.weight).collect::<Vec<u32>>(), values);
+		assert_eq!(infos[1].fraction, FixedU128::saturating_from_rational(values[1], u32::MAX));
+		assert!(infos[0].fraction + infos[1].fraction <= FixedU128::one());
+		assert!(infos[0].fraction + infos[1].fraction > FixedU128::saturating_from_rational(999_999, 1_000_000));
+	});
+}
+
+#[test]
+fn test_get_weight_infos_leaves_out_stale_edges() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_neuron(55, 66);
+		let neuron_b = subscribe_ok_neuron(66, 66);
+		assert_ok!(SubtensorModule::set_weights(Origin::signed(55), vec![neuron_a.uid, neuron_b.uid], vec![1, 1]));
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(66), 66));
+
+		// --- The stored row still holds the stale edge, the returned weights are re-normalized.
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a).0, vec![neuron_a.uid, neuron_b.uid]);
+		let infos = SubtensorModule::get_weight_infos(neuron_a.uid);
+		assert_eq!(infos.len(), 1);
+		assert_eq!((infos[0].uid, infos[0].weight), (neuron_a.uid, u32::MAX));
+		assert_eq!(infos[0].fraction, FixedU128::one());
+
+		assert!(SubtensorModule::get_weight_infos(neuron_b.uid).is_empty());
+	});
+}
+
+#[test]
+fn test_get_weight_matrix_pages() {
+	new_test_ext().execute_with(|| {
+		let neurons: Vec<_> = (0..5).map(|hotkey| subscribe_ok_neuron(hotkey, 66)).collect();
+		assert_ok!(SubtensorModule::unsubscribe(Origin::signed(2), 2));
+
+		let page = SubtensorModule::get_weight_matrix(1, 3);
+		assert_eq!(page.iter().map(|row| row.uid).collect::<Vec<u64>>(), vec![neurons[1].uid, neurons[3].uid]);
+		assert_eq!(page[0].weights, SubtensorModule::get_weight_infos(neurons[1].uid));
+
+		assert_eq!(SubtensorModule::get_weight_matrix(0, u64::MAX).len(), 4);
+		assert!(SubtensorModule::get_weight_matrix(5, 10).is_empty());
+	});
+}
+
+#[test]
+fn test_get_weight_matrix_clamps_count_to_page_size() {
+	new_test_ext().execute_with(|| {
+		let page_size = pallet_subtensor::MAX_WEIGHT_MATRIX_PAGE;
+		for hotkey in 0..page_size + 1 {
+			// --- Registrations are limited to 25 per block.
+			run_to_block(hotkey / 25 + 1);
+			subscribe_ok_neuron(hotkey, 66);
+		}
+
+		assert_eq!(SubtensorModule::get_weight_matrix(0, u64::MAX).len() as u64, page_size);
+		assert_eq!(SubtensorModule::get_weight_matrix(page_size, u64::MAX).len(), 1);
+	});
+}
@@ -0,0 +1,197 @@
+use pallet_subtensor::{Error};
+use frame_support::{assert_ok, traits::Get};
+use frame_system::Trait;
+mod mock;
+use mock::*;
+use pallet_subtensor::{Call as SubtensorCall};
+use frame_support::dispatch::{GetDispatchInfo, DispatchInfo};
+use frame_support::weights::{DispatchClass, Pays};
+
+/********************************************
+	weights_key::do_set_weights_key() tests
+*********************************************/
+#[test]
+fn fee_and_weight_of_set_weights_key() {
+	new_test_ext().execute_with(|| {
+		let call = SubtensorCall::set_weights_key(1, 2);
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: <Test as Trait>::DbWeight::get().reads_writes(6, 3),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::Yes
+		});
+	});
+}
+
+#[test]
+fn test_set_weights_key_ok() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 99));
+		assert_eq!(SubtensorModule::weights_key(neuron.uid), Some(99));
+		assert_eq!(SubtensorModule::uid_for_weights_key(99), Some(neuron.uid));
+
+		// --- A new weights key replaces the previous one.
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 98));
+		assert_eq!(SubtensorModule::weights_key(neuron.uid), Some(98));
+		assert_eq!(SubtensorModule::uid_for_weights_key(99), None);
+	});
+}
+
+#[test]
+fn test_set_weights_key_again_is_noop() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 99));
+
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 99));
+		assert_eq!(SubtensorModule::weights_key(neuron.uid), Some(99));
+		assert_eq!(SubtensorModule::uid_for_weights_key(99), Some(neuron.uid));
+	});
+}
+
+#[test]
+fn test_set_weights_key_err_not_coldkey() {
+	new_test_ext().execute_with(|| {
+		let _neuron = subscribe_ok_confirmed_neuron(1, 667);
+
+		let result = SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(1), 1, 99);
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+
+		let result = SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 2, 99);
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+#[test]
+fn test_set_weights_key_err_key_in_use_or_hotkey() {
+	new_test_ext().execute_with(|| {
+		let _neuron_a = subscribe_ok_confirmed_neuron(1, 667);
+		let _neuron_b = subscribe_ok_confirmed_neuron(2, 667);
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 99));
+
+		let result = SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 2, 99);
+		assert_eq!(result, Err(Error::<Test>::WeightsKeyInUse.into()));
+
+		let result = SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 2, 1);
+		assert_eq!(result, Err(Error::<Test>::WeightsKeyIsHotkey.into()));
+	});
+}
+
+/********************************************
+	weights_key::do_revoke_weights_key() tests
+*********************************************/
+#[test]
+fn fee_and_weight_of_revoke_weights_key() {
+	new_test_ext().execute_with(|| {
+		let call = SubtensorCall::revoke_weights_key(1);
+		assert_eq!(call.get_dispatch_info(), DispatchInfo {
+			weight: <Test as Trait>::DbWeight::get().reads_writes(4, 2),
+			class: DispatchClass::Normal,
+			pays_fee: Pays::Yes
+		});
+	});
+}
+
+#[test]
+fn test_revoke_weights_key_ok() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 99));
+
+		assert_ok!(SubtensorModule::revoke_weights_key(<<Test as Trait>::Origin>::signed(667), 1));
+		assert_eq!(SubtensorModule::weights_key(neuron.uid), None);
+		assert_eq!(SubtensorModule::uid_for_weights_key(99), None);
+
+		let result = SubtensorModule::set_weights(<<Test as Trait>::Origin>::signed(99), vec![neuron.uid], vec![u32::MAX]);
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+	});
+}
+
+#[test]
+fn test_revoke_weights_key_err_no_weights_key() {
+	new_test_ext().execute_with(|| {
+		let _neuron = subscribe_ok_confirmed_neuron(1, 667);
+		let result = SubtensorModule::revoke_weights_key(<<Test as Trait>::Origin>::signed(667), 1);
+		assert_eq!(result, Err(Error::<Test>::NoWeightsKey.into()));
+	});
+}
+
+#[test]
+fn test_unsubscribe_removes_weights_key() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 99));
+
+		assert_ok!(SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(1), 1));
+		assert_eq!(SubtensorModule::weights_key(neuron.uid), None);
+		assert_eq!(SubtensorModule::uid_for_weights_key(99), None);
+	});
+}
+
+#[test]
+fn test_subscribing_weights_key_revokes_it() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_confirmed_neuron(1, 667);
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 99));
+
+		let neuron_b = subscribe_ok_neuron(99, 668);
+		assert_eq!(SubtensorModule::weights_key(neuron_a.uid), None);
+		assert_eq!(SubtensorModule::uid_for_weights_key(99), None);
+
+		// --- The account now sets the weights of its own neuron.
+		assert_ok!(SubtensorModule::set_weights(<<Test as Trait>::Origin>::signed(99), vec![neuron_a.uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_b), (vec![neuron_a.uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_a.uid], vec![u32::MAX]));
+	});
+}
+
+#[test]
+fn test_swapping_in_weights_key_revokes_it() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_confirmed_neuron(1, 667);
+		let neuron_b = subscribe_ok_confirmed_neuron(2, 667);
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 99));
+
+		assert_ok!(SubtensorModule::swap_hotkey(<<Test as Trait>::Origin>::signed(667), 2, 99));
+		assert_eq!(SubtensorModule::weights_key(neuron_a.uid), None);
+		assert_eq!(SubtensorModule::uid_for_weights_key(99), None);
+		assert_eq!(SubtensorModule::get_uid_for_hotkey(&99), neuron_b.uid);
+	});
+}
+
+/********************************************
+	Calls made with a weights key
+*********************************************/
+#[test]
+fn test_weights_key_sets_and_updates_weights() {
+	new_test_ext().execute_with(|| {
+		let neuron_a = subscribe_ok_confirmed_neuron(1, 667);
+		let neuron_b = subscribe_ok_confirmed_neuron(2, 667);
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 99));
+
+		assert_ok!(SubtensorModule::set_weights(<<Test as Trait>::Origin>::signed(99), vec![neuron_b.uid], vec![u32::MAX]));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_b.uid], vec![u32::MAX]));
+
+		assert_ok!(SubtensorModule::update_weights(<<Test as Trait>::Origin>::signed(99), vec![(neuron_a.uid, u32::MAX)], None));
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_a), (vec![neuron_a.uid, neuron_b.uid], vec![u32::MAX / 2 + 1, u32::MAX / 2]));
+
+		// --- The weights of the neuron of the hotkey are untouched.
+		assert_eq!(SubtensorModule::get_weights_for_neuron(&neuron_b), (vec![neuron_b.uid], vec![u32::MAX]));
+	});
+}
+
+#[test]
+fn test_weights_key_can_not_make_other_calls() {
+	new_test_ext().execute_with(|| {
+		let neuron = subscribe_ok_confirmed_neuron(1, 667);
+		assert_ok!(SubtensorModule::set_weights_key(<<Test as Trait>::Origin>::signed(667), 1, 99));
+
+		let result = SubtensorModule::commit_weights(<<Test as Trait>::Origin>::signed(99), sp_core::H256::zero());
+		assert_eq!(result, Err(Error::<Test>::NotActive.into()));
+
+		let result = SubtensorModule::unsubscribe(<<Test as Trait>::Origin>::signed(99), 1);
+		assert_eq!(result, Err(Error::<Test>::NonAssociatedColdKey.into()));
+		assert!(SubtensorModule::is_uid_active(neuron.uid));
+	});
+}
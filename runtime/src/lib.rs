@@ -105,10 +105,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-subtensor-runtime"), // Don't change this. It'll fuck up chain upgrades
 	impl_name: create_runtime_str!("node-subtensor-client"), // I guess the same goes for this
 	authoring_version: 1,
-	spec_version: 4,
+	spec_version: 5,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 pub const MILLISECS_PER_BLOCK: u64 = 6000;
@@ -275,11 +275,44 @@ impl pallet_sudo::Trait for Runtime {
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const MaxNeurons: u64 = 4096;
+	pub const ImmunityPeriod: BlockNumber = 600;
+	pub const MaxRegistrationsPerBlock: u32 = 25;
+	pub const MaxUpdatesPerBlock: u32 = 25;
+	pub const UpdateCooldown: BlockNumber = 100;
+	pub const InactivityThreshold: BlockNumber = 14400;
+	pub const MaxLivenessChecksPerBlock: u64 = 64;
+	pub const MaxEndpointsPerNeuron: u32 = 8;
+	pub const MaxIpRangeOverrides: u32 = 64;
+	pub const IdentityDeposit: Balance = 1_000_000_000;
+	pub const MaxIdentityFieldLength: u32 = 256;
+	pub const MaxWeightsPerNeuron: u32 = 4096;
+	pub const WeightsSetRateLimit: BlockNumber = 100;
+	pub const WeightsRevealWindow: BlockNumber = 100;
+	pub const MaxWeightPrunesPerBlock: u64 = 4;
+}
+
 /// Configure the subtensor pallet in pallets/subtensor.
 impl pallet_subtensor::Trait for Runtime {
 	type Currency = Balances;
 	type Event = Event;
 	type TransactionByteFee = ();
+	type MaxNeurons = MaxNeurons;
+	type ImmunityPeriod = ImmunityPeriod;
+	type MaxRegistrationsPerBlock = MaxRegistrationsPerBlock;
+	type MaxUpdatesPerBlock = MaxUpdatesPerBlock;
+	type UpdateCooldown = UpdateCooldown;
+	type InactivityThreshold = InactivityThreshold;
+	type MaxLivenessChecksPerBlock = MaxLivenessChecksPerBlock;
+	type MaxEndpointsPerNeuron = MaxEndpointsPerNeuron;
+	type MaxIpRangeOverrides = MaxIpRangeOverrides;
+	type IdentityDeposit = IdentityDeposit;
+	type MaxIdentityFieldLength = MaxIdentityFieldLength;
+	type MaxWeightsPerNeuron = MaxWeightsPerNeuron;
+	type WeightsSetRateLimit = WeightsSetRateLimit;
+	type WeightsRevealWindow = WeightsRevealWindow;
+	type MaxWeightPrunesPerBlock = MaxWeightPrunesPerBlock;
 }
 
 parameter_types! {
@@ -325,7 +358,7 @@ construct_runtime!(
 		Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event},
 		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
-		SubtensorModule: pallet_subtensor::{Module, Call, Storage, Event<T>},
+		SubtensorModule: pallet_subtensor::{Module, Call, Config, Storage, Event<T>},
 		Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
 	}
 );
@@ -488,6 +521,28 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_subtensor_rpc_runtime_api::SubtensorApi<Block, AccountId> for Runtime {
+		fn get_uids_for_coldkey(coldkey: AccountId) -> Vec<u64> {
+			SubtensorModule::get_uids_for_coldkey(&coldkey)
+		}
+
+		fn get_total_stake_for_coldkey(coldkey: AccountId) -> u64 {
+			SubtensorModule::get_total_stake_for_coldkey(&coldkey)
+		}
+
+		fn get_weights(uid: u64) -> Vec<pallet_subtensor_rpc_runtime_api::WeightInfo> {
+			SubtensorModule::get_weight_infos(uid)
+		}
+
+		fn get_weight_matrix(start_uid: u64, count: u64) -> Vec<pallet_subtensor_rpc_runtime_api::NeuronWeights> {
+			SubtensorModule::get_weight_matrix(start_uid, count)
+		}
+
+		fn get_transport_key(uid: u64) -> Option<pallet_subtensor_rpc_runtime_api::TransportKey> {
+			SubtensorModule::get_transport_key_for_neuron(uid)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(